@@ -39,7 +39,7 @@ enum ConstructorArgs {
     Ignore,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContractVerifier {
     config: ContractVerifierConfig,
     connection_pool: ConnectionPool,
@@ -458,7 +458,10 @@ impl JobProcessor for ContractVerifier {
     const BACKOFF_MULTIPLIER: u64 = 1;
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut connection = self.connection_pool.access_storage().await.unwrap();
+        let mut connection = self
+            .acquire_connection(self.connection_pool.access_storage())
+            .await
+            .unwrap();
 
         // Time overhead for all operations except for compilation.
         const TIME_OVERHEAD: Duration = Duration::from_secs(10);
@@ -476,7 +479,10 @@ impl JobProcessor for ContractVerifier {
     }
 
     async fn save_failure(&self, job_id: usize, _started_at: Instant, error: String) {
-        let mut connection = self.connection_pool.access_storage().await.unwrap();
+        let mut connection = self
+            .acquire_connection(self.connection_pool.access_storage())
+            .await
+            .unwrap();
 
         connection
             .contract_verification_dal()
@@ -496,13 +502,16 @@ impl JobProcessor for ContractVerifier {
         job: VerificationRequest,
         started_at: Instant,
     ) -> tokio::task::JoinHandle<anyhow::Result<()>> {
-        let connection_pool = self.connection_pool.clone();
+        let this = self.clone();
         tokio::task::spawn(async move {
             tracing::info!("Started to process request with id = {}", job.id);
 
             let config: ContractVerifierConfig =
                 ContractVerifierConfig::from_env().context("ContractVerifierConfig")?;
-            let mut connection = connection_pool.access_storage().await.unwrap();
+            let mut connection = this
+                .acquire_connection(this.connection_pool.access_storage())
+                .await
+                .unwrap();
 
             let job_id = job.id;
             let verification_result = Self::verify(&mut connection, job, config).await;
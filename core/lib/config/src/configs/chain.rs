@@ -93,6 +93,38 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Hex-encoded revert selectors (e.g., `0x08c379a0`). If a transaction reverts with data starting
+    /// with one of these selectors, it is excluded from the L1 batch, and the batch is sealed.
+    pub revert_guard_selectors: Option<Vec<String>>,
+    /// Hex-encoded function selectors (e.g., `0xa9059cbb`). If a transaction calls a function with one of
+    /// these selectors, it is rejected as unexecutable.
+    pub blocked_tx_selectors: Option<Vec<String>>,
+    /// If set, each L1 batch is sealed after exactly this number of transactions (unless it's sealed earlier
+    /// by other criteria). Intended for tests and benchmarks requiring deterministic batch boundaries.
+    pub fixed_cadence_txs_per_batch: Option<usize>,
+    /// Maximum number of L2-to-L1 logs in an L1 batch. If not set, the number of logs is only limited
+    /// by the circuit geometry (which doesn't limit it at all for post-boojum protocol versions).
+    pub max_l2_to_l1_logs_per_batch: Option<usize>,
+    /// Maximum number of pubdata bytes in an L1 batch. If not set, the L1 commit limit is used; larger values
+    /// are capped by this limit as well.
+    pub max_pubdata_per_batch: Option<u64>,
+    /// Whether to log each time a seal criterion starts requesting a stricter resolution (e.g., switches
+    /// from not sealing the L1 batch to sealing it).
+    #[serde(default)]
+    pub log_seal_criteria_transitions: bool,
+    /// Whether to check `block_commit_deadline_ms` after each executed transaction as a conditional seal criterion,
+    /// in addition to the unconditional check when waiting for transactions. Since the check depends on the current time,
+    /// this makes conditional sealing non-deterministic.
+    #[serde(default)]
+    pub use_timeout_seal_criterion: bool,
+    /// Names of conditional seal criteria (as reported in metrics, e.g. `tx_encoding_size`) that should not be used
+    /// by the state keeper. Intended to quickly disable a misbehaving criterion without a recompile.
+    pub disabled_seal_criteria: Option<Vec<String>>,
+    /// Names of conditional seal criteria that should seal the L1 batch only if all of them agree, i.e., only
+    /// the least strict of their resolutions is used. Combined criteria are reported in metrics as `all_of`
+    /// unless one of them triggers sealing.
+    pub all_of_seal_criteria: Option<Vec<String>>,
 }
 
 impl StateKeeperConfig {
@@ -121,6 +153,15 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            revert_guard_selectors: None,
+            blocked_tx_selectors: None,
+            fixed_cadence_txs_per_batch: None,
+            max_l2_to_l1_logs_per_batch: None,
+            max_pubdata_per_batch: None,
+            log_seal_criteria_transitions: false,
+            use_timeout_seal_criterion: false,
+            disabled_seal_criteria: None,
+            all_of_seal_criteria: None,
         }
     }
 
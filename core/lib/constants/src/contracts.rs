@@ -110,6 +110,48 @@ pub const EC_PAIRING_PRECOMPILE_ADDRESS: Address = H160([
     0x00, 0x00, 0x00, 0x08,
 ]);
 
+/// Precompiles supported by zkSync Era.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precompile {
+    Keccak256,
+    Sha256,
+    Ecrecover,
+    EcAdd,
+    EcMul,
+    EcPairing,
+}
+
+impl Precompile {
+    const ALL: [Self; 6] = [
+        Self::Keccak256,
+        Self::Sha256,
+        Self::Ecrecover,
+        Self::EcAdd,
+        Self::EcMul,
+        Self::EcPairing,
+    ];
+
+    /// Returns the precompile deployed at the specified address, or `None` if there's no precompile at the address.
+    pub fn from_address(address: Address) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|precompile| precompile.address() == address)
+    }
+
+    /// Returns the address of this precompile.
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Keccak256 => KECCAK256_PRECOMPILE_ADDRESS,
+            Self::Sha256 => SHA256_PRECOMPILE_ADDRESS,
+            Self::Ecrecover => ECRECOVER_PRECOMPILE_ADDRESS,
+            Self::EcAdd => EC_ADD_PRECOMPILE_ADDRESS,
+            Self::EcMul => EC_MUL_PRECOMPILE_ADDRESS,
+            Self::EcPairing => EC_PAIRING_PRECOMPILE_ADDRESS,
+        }
+    }
+}
+
 pub const ERC20_TRANSFER_TOPIC: H256 = H256([
     221, 242, 82, 173, 27, 226, 200, 155, 105, 194, 176, 104, 252, 55, 141, 170, 149, 43, 167, 241,
     99, 196, 161, 22, 40, 245, 90, 77, 245, 35, 179, 239,
@@ -5,17 +5,28 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 
 use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    error, fmt,
     fs::{self, File},
+    io::{self, BufReader, Read},
+    panic,
     path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
 };
 
 use ethabi::{
-    ethereum_types::{H256, U256},
-    Contract, Function,
+    ethereum_types::{Address, H256, U256},
+    short_signature, Contract, Function, ParamType, Token,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use zksync_utils::{bytecode::hash_bytecode, bytes_to_be_words};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode, InvalidBytecodeError},
+    bytes_to_be_words,
+};
 
 pub mod test_contracts;
 
@@ -44,37 +55,534 @@ const LOADNEXT_CONTRACT_FILE: &str =
 const LOADNEXT_SIMPLE_CONTRACT_FILE: &str =
     "etc/contracts-test-data/artifacts-zk/contracts/loadnext/loadnext_contract.sol/Foo.json";
 
+static ZKSYNC_HOME: Lazy<PathBuf> = Lazy::new(|| {
+    let zksync_home = PathBuf::from(std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into()));
+    zksync_home.canonicalize().unwrap_or(zksync_home)
+});
+
+/// Returns the path to the zkSync home directory, which is used to resolve relative paths
+/// by most functions in this crate. The path is read from the ZKSYNC_HOME environment variable (defaulting to the current directory)
+/// and canonicalized once on the first call; later changes of the variable have no effect.
+pub fn zksync_home() -> &'static Path {
+    &ZKSYNC_HOME
+}
+
+/// Environment variable overriding the system contracts directory; see [`system_contracts_dir()`].
+pub const SYSTEM_CONTRACTS_DIR_ENV_VAR: &str = "SYS_CONTRACTS_ARTIFACTS_DIR";
+
+static SYSTEM_CONTRACTS_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let dir_override = std::env::var_os(SYSTEM_CONTRACTS_DIR_ENV_VAR);
+    resolve_system_contracts_dir(dir_override.as_deref().map(Path::new))
+});
+
+fn resolve_system_contracts_dir(dir_override: Option<&Path>) -> PathBuf {
+    let dir = dir_override.unwrap_or_else(|| Path::new("contracts/system-contracts"));
+    zksync_home().join(dir)
+}
+
+/// Returns the root directory of system contracts, which contains system contract and bootloader artifacts.
+/// If the `SYS_CONTRACTS_ARTIFACTS_DIR` environment variable is set, it takes precedence over the default
+/// `$ZKSYNC_HOME/contracts/system-contracts` (a relative override is still resolved relative to ZKSYNC_HOME).
+/// Like [`zksync_home()`], the directory is determined once on the first call.
+pub fn system_contracts_dir() -> &'static Path {
+    &SYSTEM_CONTRACTS_DIR
+}
+
+/// Error loading a contract artifact (ABI or bytecode).
+#[derive(Debug)]
+pub enum ContractLoadError {
+    /// Artifact file doesn't exist.
+    NotFound { path: PathBuf },
+    /// Artifact file exists, but cannot be read.
+    Io { path: PathBuf, source: io::Error },
+    /// Artifact file is not valid JSON.
+    InvalidJson {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// Artifact doesn't contain a required field (e.g., `abi` or `bytecode`).
+    MissingField {
+        path: PathBuf,
+        field: &'static str,
+        /// Top-level keys present in the artifact.
+        present_keys: Vec<String>,
+    },
+    /// Artifact field has an invalid format.
+    InvalidField {
+        path: PathBuf,
+        field: &'static str,
+        reason: String,
+    },
+    /// Bytecode contains placeholders for libraries that were not linked. Libraries are identified
+    /// by their placeholder IDs, i.e. the 34 hex chars between `__$` and `$__` in the bytecode.
+    UnlinkedLibraries {
+        path: PathBuf,
+        libraries: Vec<String>,
+    },
+    /// Bytecode cannot be deployed on zkEVM (e.g., its length is not divisible by 32 or exceeds the limit).
+    InvalidBytecode {
+        path: PathBuf,
+        source: InvalidBytecodeError,
+    },
+    /// Bytecode hash is not in the set of known code hashes; returned by [`read_bytecode_if_known()`].
+    UnknownCode { path: PathBuf, hash: H256 },
+}
+
+impl fmt::Display for ContractLoadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { path } => write!(formatter, "file {:?} not found", path),
+            Self::Io { path, source } => {
+                write!(formatter, "failed to read file {:?}: {}", path, source)
+            }
+            Self::InvalidJson { path, source } => {
+                write!(formatter, "failed to parse file {:?}: {}", path, source)
+            }
+            Self::MissingField {
+                path,
+                field,
+                present_keys,
+            } => write!(
+                formatter,
+                "field `{}` not found in {:?}; present keys: [{}]",
+                field,
+                path,
+                present_keys.join(", ")
+            ),
+            Self::InvalidField {
+                path,
+                field,
+                reason,
+            } => write!(
+                formatter,
+                "field `{}` in {:?} is invalid: {}",
+                field, path, reason
+            ),
+            Self::UnlinkedLibraries { path, libraries } => write!(
+                formatter,
+                "bytecode in {:?} contains unlinked libraries: [{}]",
+                path,
+                libraries.join(", ")
+            ),
+            Self::InvalidBytecode { path, source } => {
+                write!(formatter, "bytecode in {:?} is invalid: {}", path, source)
+            }
+            Self::UnknownCode { path, hash } => write!(
+                formatter,
+                "bytecode in {:?} has hash {:?}, which is not in the set of known code hashes",
+                path, hash
+            ),
+        }
+    }
+}
+
+impl ContractLoadError {
+    fn io(path: PathBuf, source: io::Error) -> Self {
+        if source.kind() == io::ErrorKind::NotFound {
+            Self::NotFound { path }
+        } else {
+            Self::Io { path, source }
+        }
+    }
+}
+
+impl error::Error for ContractLoadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::InvalidJson { source, .. } => Some(source),
+            Self::InvalidBytecode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+fn try_read_file_to_json_value(
+    path: impl AsRef<Path>,
+) -> Result<serde_json::Value, ContractLoadError> {
+    let path = zksync_home().join(path);
+    let file = File::open(&path).map_err(|source| ContractLoadError::io(path.clone(), source))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|source| ContractLoadError::InvalidJson { path, source })
+}
+
 fn read_file_to_json_value(path: impl AsRef<Path>) -> serde_json::Value {
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-    let path = Path::new(&zksync_home).join(path);
-    serde_json::from_reader(
-        File::open(&path).unwrap_or_else(|e| panic!("Failed to open file {:?}: {}", path, e)),
-    )
-    .unwrap_or_else(|e| panic!("Failed to parse file {:?}: {}", path, e))
+    try_read_file_to_json_value(path).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Loads a contract ABI from the artifact at the specified path (relative to ZKSYNC_HOME, unless absolute).
+/// Unlike [`load_contract()`], returns an error instead of panicking, e.g. if ZKSYNC_HOME points
+/// to a partial checkout without the artifact.
+pub fn try_load_contract<P: AsRef<Path>>(path: P) -> Result<Contract, ContractLoadError> {
+    let mut artifact = try_read_file_to_json_value(&path)?;
+    abi_from_artifact(&mut artifact).map_err(|err| err.with_path(zksync_home().join(path)))
+}
+
+/// Loads a contract ABI from the raw artifact JSON, e.g. embedded into the binary via `include_bytes!`.
+///
+/// # Panics
+///
+/// Panics if the artifact is not valid JSON or doesn't contain a valid `abi` field.
+pub fn load_contract_from_slice(bytes: &[u8]) -> Contract {
+    let mut artifact: serde_json::Value = serde_json::from_slice(bytes)
+        .unwrap_or_else(|err| panic!("Failed to parse contract artifact: {}", err));
+    abi_from_artifact(&mut artifact)
+        .unwrap_or_else(|err| panic!("Failed to load contract from artifact: {}", err))
 }
 
+/// Error extracting a field from an artifact. Converted to [`ContractLoadError`] if the artifact path is known.
+#[derive(Debug)]
+enum ArtifactFieldError {
+    Missing(&'static str, Vec<String>),
+    Invalid(&'static str, String),
+    UnlinkedLibraries(Vec<String>),
+}
+
+impl fmt::Display for ArtifactFieldError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(field, present_keys) => write!(
+                formatter,
+                "field `{}` not found; present keys: [{}]",
+                field,
+                present_keys.join(", ")
+            ),
+            Self::Invalid(field, reason) => {
+                write!(formatter, "field `{}` is invalid: {}", field, reason)
+            }
+            Self::UnlinkedLibraries(libraries) => write!(
+                formatter,
+                "bytecode contains unlinked libraries: [{}]",
+                libraries.join(", ")
+            ),
+        }
+    }
+}
+
+impl ArtifactFieldError {
+    fn missing(field: &'static str, artifact: &serde_json::Value) -> Self {
+        let present_keys = artifact
+            .as_object()
+            .map(|object| object.keys().cloned().collect())
+            .unwrap_or_default();
+        Self::Missing(field, present_keys)
+    }
+
+    fn with_path(self, path: PathBuf) -> ContractLoadError {
+        match self {
+            Self::Missing(field, present_keys) => ContractLoadError::MissingField {
+                path,
+                field,
+                present_keys,
+            },
+            Self::Invalid(field, reason) => ContractLoadError::InvalidField {
+                path,
+                field,
+                reason,
+            },
+            Self::UnlinkedLibraries(libraries) => {
+                ContractLoadError::UnlinkedLibraries { path, libraries }
+            }
+        }
+    }
+}
+
+fn abi_from_artifact(artifact: &mut serde_json::Value) -> Result<Contract, ArtifactFieldError> {
+    let abi = artifact["abi"].take();
+    if abi.is_null() {
+        return Err(ArtifactFieldError::missing("abi", artifact));
+    }
+    serde_json::from_value(abi).map_err(|err| ArtifactFieldError::Invalid("abi", err.to_string()))
+}
+
+/// Extracts bytecode from an artifact. The bytecode may be stored either as a `0x`-prefixed hex string,
+/// or as an array of byte values (as emitted by some non-standard compilers).
+fn bytecode_from_artifact(artifact: &serde_json::Value) -> Result<Vec<u8>, ArtifactFieldError> {
+    bytecode_from_artifact_linked(artifact, &HashMap::new())
+}
+
+/// Same as [`bytecode_from_artifact()`], but substitutes placeholders of the libraries in `links`
+/// (keyed by placeholder IDs) with their addresses.
+fn bytecode_from_artifact_linked(
+    artifact: &serde_json::Value,
+    links: &HashMap<String, Address>,
+) -> Result<Vec<u8>, ArtifactFieldError> {
+    let invalid_bytecode = |reason: String| ArtifactFieldError::Invalid("bytecode", reason);
+
+    if let Some(bytes) = artifact["bytecode"].as_array() {
+        return bytes
+            .iter()
+            .map(|byte| {
+                byte.as_u64()
+                    .and_then(|value| u8::try_from(value).ok())
+                    .ok_or_else(|| invalid_bytecode(format!("invalid byte {}", byte)))
+            })
+            .collect();
+    }
+
+    // Besides the default layout, supports Foundry artifacts (`bytecode.object`) and Hardhat artifacts
+    // only containing `deployedBytecode`.
+    let bytecode = artifact["bytecode"]
+        .as_str()
+        .or_else(|| artifact["bytecode"]["object"].as_str())
+        .or_else(|| artifact["deployedBytecode"].as_str())
+        .ok_or_else(|| ArtifactFieldError::missing("bytecode", artifact))?
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid_bytecode("bytecode is not 0x-prefixed hex".to_owned()))?;
+    let bytecode = link_libraries(bytecode, links)?;
+
+    hex::decode(bytecode.as_ref()).map_err(|err| invalid_bytecode(err.to_string()))
+}
+
+const LIBRARY_PLACEHOLDER_PREFIX: &str = "__$";
+const LIBRARY_PLACEHOLDER_SUFFIX: &str = "$__";
+
+/// Returns IDs of libraries with placeholders in the hex-encoded `bytecode`, in the order of their first occurrence.
+fn unlinked_libraries(bytecode: &str) -> Vec<String> {
+    let mut libraries = Vec::<String>::new();
+    let mut rest = bytecode;
+    while let Some(start) = rest.find(LIBRARY_PLACEHOLDER_PREFIX) {
+        rest = &rest[start + LIBRARY_PLACEHOLDER_PREFIX.len()..];
+        let Some(end) = rest.find(LIBRARY_PLACEHOLDER_SUFFIX) else {
+            break;
+        };
+        let library = &rest[..end];
+        if !libraries.iter().any(|known| known == library) {
+            libraries.push(library.to_owned());
+        }
+        rest = &rest[end + LIBRARY_PLACEHOLDER_SUFFIX.len()..];
+    }
+    libraries
+}
+
+fn link_libraries<'a>(
+    bytecode: &'a str,
+    links: &HashMap<String, Address>,
+) -> Result<Cow<'a, str>, ArtifactFieldError> {
+    let libraries = unlinked_libraries(bytecode);
+    if libraries.is_empty() {
+        return Ok(Cow::Borrowed(bytecode));
+    }
+    let unresolved: Vec<_> = libraries
+        .iter()
+        .filter(|library| !links.contains_key(*library))
+        .cloned()
+        .collect();
+    if !unresolved.is_empty() {
+        return Err(ArtifactFieldError::UnlinkedLibraries(unresolved));
+    }
+
+    let mut linked = bytecode.to_owned();
+    for library in &libraries {
+        let placeholder = format!(
+            "{}{}{}",
+            LIBRARY_PLACEHOLDER_PREFIX, library, LIBRARY_PLACEHOLDER_SUFFIX
+        );
+        linked = linked.replace(&placeholder, &hex::encode(links[library]));
+    }
+    Ok(Cow::Owned(linked))
+}
+
+/// Process-wide cache of loaded contract ABIs keyed by the resolved artifact path.
+static CONTRACT_CACHE: Lazy<Mutex<HashMap<PathBuf, Arc<Contract>>>> = Lazy::new(Mutex::default);
+
+/// Same as [`try_load_contract()`], but caches loaded ABIs process-wide, so that only the first load
+/// of a particular artifact reads it from disk. The returned ABI is shared between all callers and cannot be mutated;
+/// changes to the artifact on disk after it was loaded are not picked up. Errors are not cached.
+pub fn try_load_contract_cached<P: AsRef<Path>>(
+    path: P,
+) -> Result<Arc<Contract>, ContractLoadError> {
+    let path = zksync_home().join(path);
+    if let Some(contract) = CONTRACT_CACHE.lock().unwrap().get(&path) {
+        return Ok(contract.clone());
+    }
+
+    let contract = Arc::new(try_load_contract(&path)?);
+    let mut cache = CONTRACT_CACHE.lock().unwrap();
+    // If the contract was loaded concurrently by another thread, return the cached ABI.
+    Ok(cache.entry(path).or_insert(contract).clone())
+}
+
+/// Panicking version of [`try_load_contract_cached()`].
+pub fn load_contract_cached<P: AsRef<Path> + std::fmt::Debug>(path: P) -> Arc<Contract> {
+    try_load_contract_cached(&path)
+        .unwrap_or_else(|err| panic!("Failed to load contract from {:?}: {}", path, err))
+}
+
+/// Loads a contract ABI via [`load_contract_cached()`] and clones it. Used by the public loaders of well-known contracts,
+/// so that each of their artifacts is read and parsed once per process.
+fn cached_contract<P: AsRef<Path> + std::fmt::Debug>(path: P) -> Contract {
+    Contract::clone(&load_contract_cached(path))
+}
+
+/// Loads a contract ABI if the artifact exists.
 pub fn load_contract_if_present<P: AsRef<Path> + std::fmt::Debug>(path: P) -> Option<Contract> {
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-    let path = Path::new(&zksync_home).join(path);
-    path.exists().then(|| {
-        serde_json::from_value(read_file_to_json_value(&path)["abi"].take())
-            .unwrap_or_else(|e| panic!("Failed to parse contract abi from file {:?}: {}", path, e))
-    })
+    match try_load_contract(path) {
+        Ok(contract) => Some(contract),
+        Err(ContractLoadError::NotFound { .. }) => None,
+        Err(err) => panic!("Failed to load contract: {}", err),
+    }
 }
 
+/// Loads a contract ABI. The artifact is read from disk on each call; use [`load_contract_cached()`]
+/// to load frequently used ABIs once per process.
 pub fn load_contract<P: AsRef<Path> + std::fmt::Debug>(path: P) -> Contract {
-    load_contract_if_present(&path).unwrap_or_else(|| {
-        panic!("Failed to load contract from {:?}", path);
-    })
+    try_load_contract(&path)
+        .unwrap_or_else(|err| panic!("Failed to load contract from {:?}: {}", path, err))
 }
 
 pub fn load_sys_contract(contract_name: &str) -> Contract {
-    load_contract(format!(
-        "contracts/system-contracts/artifacts-zk/cache-zk/solpp-generated-contracts/{0}.sol/{0}.json",
-        contract_name
+    load_sys_contract_qualified(contract_name, contract_name)
+}
+
+/// Loads a system contract declared in a source file with a different name (i.e., `{source_file}.sol`).
+pub fn load_sys_contract_qualified(source_file: &str, contract_name: &str) -> Contract {
+    cached_contract(sys_contract_artifact_path(source_file, contract_name))
+}
+
+fn sys_contract_artifact_path(source_file: &str, contract_name: &str) -> PathBuf {
+    system_contracts_dir().join(format!(
+        "artifacts-zk/cache-zk/solpp-generated-contracts/{0}.sol/{1}.json",
+        source_file, contract_name
     ))
 }
 
+/// Returns selectors of all functions declared in the contract ABI.
+pub fn abi_selector_set(contract: &Contract) -> BTreeSet<[u8; 4]> {
+    contract
+        .functions()
+        .map(|function| function.short_signature())
+        .collect()
+}
+
+/// Returns `required` selectors that are not declared in the contract ABI.
+/// An empty result means that the ABI covers all `required` selectors.
+pub fn abi_covers(contract: &Contract, required: &BTreeSet<[u8; 4]>) -> Vec<[u8; 4]> {
+    let declared = abi_selector_set(contract);
+    required.difference(&declared).copied().collect()
+}
+
+/// Returns the signature hash (i.e., `topic0` of emitted logs) of the event with the specified name
+/// declared in the contract ABI, or `None` if there is no such event. If the event is overloaded,
+/// returns the signature of the first overload.
+pub fn event_signature(contract: &Contract, event_name: &str) -> Option<H256> {
+    let event = contract.events_by_name(event_name).ok()?.first()?;
+    Some(event.signature())
+}
+
+/// Returns the selector of the function with the specified name declared in the contract ABI, or `None`
+/// if there is no such function. Overloaded functions can be disambiguated by the number of arguments
+/// (`arg_count`); if `arg_count` is not specified and the function is overloaded, returns `None`.
+pub fn function_selector(
+    contract: &Contract,
+    function_name: &str,
+    arg_count: Option<usize>,
+) -> Option<[u8; 4]> {
+    let overloads = contract.functions_by_name(function_name).ok()?;
+    let function = match arg_count {
+        Some(arg_count) => overloads
+            .iter()
+            .find(|function| function.inputs.len() == arg_count)?,
+        None if overloads.len() == 1 => &overloads[0],
+        None => return None,
+    };
+    Some(function.short_signature())
+}
+
+/// Returns `(name, selector)` pairs for all functions declared in the contract ABI, e.g. to build a selector directory.
+/// Overloaded functions are named by their full signature (e.g., `safeTransferFrom(address,address,uint256)`)
+/// so that names are unique.
+pub fn contract_selectors(contract: &Contract) -> Vec<(String, [u8; 4])> {
+    contract
+        .functions
+        .values()
+        .flat_map(|overloads| {
+            overloads.iter().map(move |function| {
+                let name = if overloads.len() > 1 {
+                    let param_types = function.inputs.iter().map(|param| &param.kind);
+                    full_signature(&function.name, param_types)
+                } else {
+                    function.name.clone()
+                };
+                (name, function.short_signature())
+            })
+        })
+        .collect()
+}
+
+/// Returns `(name, topic)` pairs for all events declared in the contract ABI, where `topic` is the event signature hash
+/// (i.e., `topic0` of emitted logs). Overloaded events are named by their full signature, same as in [`contract_selectors()`].
+pub fn contract_event_topics(contract: &Contract) -> Vec<(String, H256)> {
+    contract
+        .events
+        .values()
+        .flat_map(|overloads| {
+            overloads.iter().map(move |event| {
+                let name = if overloads.len() > 1 {
+                    let param_types = event.inputs.iter().map(|param| &param.kind);
+                    full_signature(&event.name, param_types)
+                } else {
+                    event.name.clone()
+                };
+                (name, event.signature())
+            })
+        })
+        .collect()
+}
+
+fn full_signature<'a>(name: &str, param_types: impl Iterator<Item = &'a ParamType>) -> String {
+    let param_types: Vec<_> = param_types.map(ParamType::to_string).collect();
+    format!("{}({})", name, param_types.join(","))
+}
+
+/// Revert reason decoded by [`decode_revert()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedRevert {
+    /// Standard `Error(string)` revert, e.g. produced by `require(condition, "message")`.
+    Error(String),
+    /// Standard `Panic(uint256)` revert, e.g. produced on arithmetic overflow.
+    Panic(U256),
+    /// Custom error declared in the contract ABI.
+    Custom { name: String, args: Vec<Token> },
+}
+
+/// Decodes revert data returned by a call to `contract`. Besides standard `Error(string)` and `Panic(uint256)`
+/// reverts, recognizes custom errors declared in the contract ABI.
+///
+/// Returns `None` if the revert data has an unknown selector or cannot be decoded.
+pub fn decode_revert(contract: &Contract, revert_data: &[u8]) -> Option<DecodedRevert> {
+    if revert_data.len() < 4 {
+        return None;
+    }
+    let (selector, data) = revert_data.split_at(4);
+
+    if selector == short_signature("Error", &[ParamType::String]) {
+        let tokens = ethabi::decode(&[ParamType::String], data).ok()?;
+        return tokens
+            .into_iter()
+            .next()?
+            .into_string()
+            .map(DecodedRevert::Error);
+    }
+    if selector == short_signature("Panic", &[ParamType::Uint(256)]) {
+        let tokens = ethabi::decode(&[ParamType::Uint(256)], data).ok()?;
+        return tokens
+            .into_iter()
+            .next()?
+            .into_uint()
+            .map(DecodedRevert::Panic);
+    }
+
+    let custom_error = contract
+        .errors()
+        .find(|custom_error| custom_error.signature()[..4] == *selector)?;
+    let args = custom_error.decode(data).ok()?;
+    Some(DecodedRevert::Custom {
+        name: custom_error.name.clone(),
+        args,
+    })
+}
+
 pub fn read_contract_abi(path: impl AsRef<Path>) -> String {
     read_file_to_json_value(path)["abi"]
         .as_str()
@@ -83,27 +591,31 @@ pub fn read_contract_abi(path: impl AsRef<Path>) -> String {
 }
 
 pub fn governance_contract() -> Contract {
-    load_contract_if_present(GOVERNANCE_CONTRACT_FILE).expect("Governance contract not found")
+    match try_load_contract_cached(GOVERNANCE_CONTRACT_FILE) {
+        Ok(contract) => Contract::clone(&contract),
+        Err(ContractLoadError::NotFound { .. }) => panic!("Governance contract not found"),
+        Err(err) => panic!("Failed to load contract: {}", err),
+    }
 }
 
 pub fn zksync_contract() -> Contract {
-    load_contract(ZKSYNC_CONTRACT_FILE)
+    cached_contract(ZKSYNC_CONTRACT_FILE)
 }
 
 pub fn multicall_contract() -> Contract {
-    load_contract(MULTICALL3_CONTRACT_FILE)
+    cached_contract(MULTICALL3_CONTRACT_FILE)
 }
 
 pub fn erc20_contract() -> Contract {
-    load_contract(IERC20_CONTRACT_FILE)
+    cached_contract(IERC20_CONTRACT_FILE)
 }
 
 pub fn l2_bridge_contract() -> Contract {
-    load_contract(L2_BRIDGE_CONTRACT_FILE)
+    cached_contract(L2_BRIDGE_CONTRACT_FILE)
 }
 
 pub fn verifier_contract() -> Contract {
-    load_contract(VERIFIER_CONTRACT_FILE)
+    cached_contract(VERIFIER_CONTRACT_FILE)
 }
 
 #[derive(Debug, Clone)]
@@ -116,31 +628,120 @@ pub struct TestContract {
     pub factory_deps: Vec<Vec<u8>>,
 }
 
+impl TestContract {
+    /// Starts building a test contract with the specified bytecode.
+    pub fn builder(bytecode: Vec<u8>) -> TestContractBuilder {
+        TestContractBuilder {
+            bytecode,
+            contract: Contract::default(),
+            factory_deps: vec![],
+        }
+    }
+
+    /// Reads both bytecode and ABI from the artifact at the specified path (relative to ZKSYNC_HOME, unless absolute).
+    /// The returned contract has no factory dependencies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the artifact cannot be read or doesn't contain a valid ABI or bytecode.
+    pub fn from_artifact(path: impl AsRef<Path>) -> Self {
+        let path = zksync_home().join(path);
+        let mut artifact = read_file_to_json_value(&path);
+        let bytecode = bytecode_from_artifact(&artifact)
+            .unwrap_or_else(|err| panic!("{}", err.with_path(path.clone())));
+        let contract = abi_from_artifact(&mut artifact)
+            .unwrap_or_else(|err| panic!("{}", err.with_path(path.clone())));
+        Self::builder(bytecode).with_abi(contract).build()
+    }
+
+    /// Returns hashes of all factory dependencies, e.g. to be included into a deploy transaction.
+    pub fn factory_dep_hashes(&self) -> Vec<H256> {
+        self.factory_deps
+            .iter()
+            .map(|dep| hash_bytecode(dep))
+            .collect()
+    }
+
+    /// Returns the total size of factory dependencies published by a transaction deploying this contract.
+    /// Since the contract bytecode is itself published as a factory dependency, it is included
+    /// in the returned value along with [`Self::factory_deps`].
+    pub fn factory_deps_total_bytes(&self) -> usize {
+        self.bytecode.len() + factory_deps_total_bytes(&self.factory_deps)
+    }
+
+    /// Removes duplicate factory dependencies (i.e., ones with the same bytecode hash), retaining
+    /// the first occurrence of each dependency.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of factory dependencies is not a valid bytecode.
+    pub fn with_deduplicated_factory_deps(mut self) -> Self {
+        let mut seen_hashes = HashSet::with_capacity(self.factory_deps.len());
+        self.factory_deps
+            .retain(|dep| seen_hashes.insert(hash_bytecode(dep)));
+        self
+    }
+}
+
+/// Builder for [`TestContract`]s.
+#[derive(Debug, Clone)]
+pub struct TestContractBuilder {
+    bytecode: Vec<u8>,
+    contract: Contract,
+    factory_deps: Vec<Vec<u8>>,
+}
+
+impl TestContractBuilder {
+    /// Sets the contract ABI. If not set, the ABI is empty.
+    pub fn with_abi(mut self, contract: Contract) -> Self {
+        self.contract = contract;
+        self
+    }
+
+    /// Adds a factory dependency for the contract.
+    pub fn add_factory_dep(mut self, dep: Vec<u8>) -> Self {
+        self.factory_deps.push(dep);
+        self
+    }
+
+    pub fn build(self) -> TestContract {
+        TestContract {
+            bytecode: self.bytecode,
+            contract: self.contract,
+            factory_deps: self.factory_deps,
+        }
+    }
+}
+
+/// Returns the total size of the provided factory dependencies in bytes.
+pub fn factory_deps_total_bytes(deps: &[Vec<u8>]) -> usize {
+    deps.iter().map(Vec::len).sum()
+}
+
 /// Reads test contract bytecode and its ABI.
 pub fn get_loadnext_contract() -> TestContract {
     let bytecode = read_bytecode(LOADNEXT_CONTRACT_FILE);
     let dep = read_bytecode(LOADNEXT_SIMPLE_CONTRACT_FILE);
 
-    TestContract {
-        bytecode,
-        contract: loadnext_contract(),
-        factory_deps: vec![dep],
-    }
+    TestContract::builder(bytecode)
+        .with_abi(loadnext_contract())
+        .add_factory_dep(dep)
+        .build()
 }
 
 // Returns loadnext contract and its factory dependencies
 pub fn loadnext_contract() -> Contract {
-    load_contract("etc/contracts-test-data/artifacts-zk/contracts/loadnext/loadnext_contract.sol/LoadnextContract.json")
+    cached_contract("etc/contracts-test-data/artifacts-zk/contracts/loadnext/loadnext_contract.sol/LoadnextContract.json")
 }
 
 pub fn loadnext_simple_contract() -> Contract {
-    load_contract(
+    cached_contract(
         "etc/contracts-test-data/artifacts-zk/contracts/loadnext/loadnext_contract.sol/Foo.json",
     )
 }
 
 pub fn fail_on_receive_contract() -> Contract {
-    load_contract(FAIL_ON_RECEIVE_CONTRACT_FILE)
+    cached_contract(FAIL_ON_RECEIVE_CONTRACT_FILE)
 }
 
 pub fn deployer_contract() -> Contract {
@@ -155,24 +756,283 @@ pub fn known_codes_contract() -> Contract {
     load_sys_contract("KnownCodesStorage")
 }
 
+pub fn bootloader_utilities_contract() -> Contract {
+    load_sys_contract("BootloaderUtilities")
+}
+
+/// Commonly used functions of the `BootloaderUtilities` system contract.
+#[derive(Debug, Clone)]
+pub struct BootloaderUtilitiesFunctions {
+    pub contract: Contract,
+    /// `getTransactionHashes(Transaction)`; returns the transaction hash and the signed transaction hash.
+    pub get_transaction_hashes: Function,
+}
+
+impl Default for BootloaderUtilitiesFunctions {
+    fn default() -> Self {
+        let contract = bootloader_utilities_contract();
+        let get_transaction_hashes = contract
+            .function("getTransactionHashes")
+            .expect("getTransactionHashes function not found")
+            .clone();
+        Self {
+            contract,
+            get_transaction_hashes,
+        }
+    }
+}
+
+pub fn system_context_contract() -> Contract {
+    load_sys_contract("SystemContext")
+}
+
+/// Typed accessor for the setters of the `SystemContext` system contract, which store block and chain parameters.
+#[derive(Debug, Clone)]
+pub struct SystemContextAbi {
+    pub contract: Contract,
+    /// `setTxOrigin(address)`
+    pub set_tx_origin: Function,
+    /// `setGasPrice(uint256)`
+    pub set_gas_price: Function,
+    /// `setChainId(uint256)`
+    pub set_chain_id: Function,
+    /// `setNewBatch(..)`; starts a new L1 batch.
+    pub set_new_batch: Function,
+    /// `setL2Block(..)`; starts a new L2 block (aka miniblock).
+    pub set_l2_block: Function,
+}
+
+impl Default for SystemContextAbi {
+    fn default() -> Self {
+        let contract = system_context_contract();
+        let function = |name: &str| {
+            contract
+                .function(name)
+                .unwrap_or_else(|_| panic!("{name} function not found"))
+                .clone()
+        };
+        Self {
+            set_tx_origin: function("setTxOrigin"),
+            set_gas_price: function("setGasPrice"),
+            set_chain_id: function("setChainId"),
+            set_new_batch: function("setNewBatch"),
+            set_l2_block: function("setL2Block"),
+            contract,
+        }
+    }
+}
+
 /// Reads bytecode from the path RELATIVE to the ZKSYNC_HOME environment variable.
 pub fn read_bytecode(relative_path: impl AsRef<Path>) -> Vec<u8> {
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-    let artifact_path = Path::new(&zksync_home).join(relative_path);
+    let artifact_path = zksync_home().join(relative_path);
     read_bytecode_from_path(artifact_path)
 }
-/// Reads bytecode from a given path.
+
+/// Same as [`read_bytecode()`], but also returns the bytecode hash (as computed by [`hash_bytecode()`]).
+///
+/// # Panics
+///
+/// In addition to panics in [`read_bytecode()`], panics if the bytecode is invalid and thus cannot be hashed.
+pub fn read_bytecode_with_hash(relative_path: impl AsRef<Path>) -> (Vec<u8>, H256) {
+    let bytecode = read_bytecode(relative_path);
+    let hash = hash_bytecode(&bytecode);
+    (bytecode, hash)
+}
+
+/// Reads bytecode from the path RELATIVE to the ZKSYNC_HOME environment variable.
+/// Unlike [`read_bytecode()`], returns an error instead of panicking.
+pub fn try_read_bytecode(relative_path: impl AsRef<Path>) -> Result<Vec<u8>, ContractLoadError> {
+    try_read_bytecode_from_path(zksync_home().join(relative_path))
+}
+
+/// Reads bytecode from a given path. The bytecode may be stored either as a `0x`-prefixed hex string,
+/// or as an array of byte values (as emitted by some non-standard compilers). Besides the top-level `bytecode` field,
+/// the bytecode is looked up in `bytecode.object` (Foundry artifacts) and `deployedBytecode` (Hardhat artifacts).
 pub fn read_bytecode_from_path(artifact_path: PathBuf) -> Vec<u8> {
-    let artifact = read_file_to_json_value(artifact_path.clone());
+    try_read_bytecode_from_path(artifact_path).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    let bytecode = artifact["bytecode"]
-        .as_str()
-        .unwrap_or_else(|| panic!("Bytecode not found in {:?}", artifact_path))
-        .strip_prefix("0x")
-        .unwrap_or_else(|| panic!("Bytecode in {:?} is not hex", artifact_path));
+/// Fallible version of [`read_bytecode_from_path()`].
+pub fn try_read_bytecode_from_path(artifact_path: PathBuf) -> Result<Vec<u8>, ContractLoadError> {
+    let artifact = try_read_file_to_json_value(&artifact_path)?;
+    bytecode_from_artifact(&artifact).map_err(|err| err.with_path(artifact_path))
+}
 
-    hex::decode(bytecode)
-        .unwrap_or_else(|err| panic!("Can't decode bytecode in {:?}: {}", artifact_path, err))
+/// Reads bytecode from the path RELATIVE to the ZKSYNC_HOME environment variable and checks that it can be deployed
+/// on zkEVM using [`validate_bytecode()`].
+///
+/// # Panics
+///
+/// Panics if the bytecode cannot be read or is invalid.
+pub fn read_valid_bytecode(relative_path: impl AsRef<Path>) -> Vec<u8> {
+    try_read_valid_bytecode(relative_path).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Fallible version of [`read_valid_bytecode()`]. Returns [`ContractLoadError::InvalidBytecode`]
+/// if the bytecode is read successfully, but fails validation.
+pub fn try_read_valid_bytecode(
+    relative_path: impl AsRef<Path>,
+) -> Result<Vec<u8>, ContractLoadError> {
+    let artifact_path = zksync_home().join(relative_path);
+    let bytecode = try_read_bytecode_from_path(artifact_path.clone())?;
+    validate_bytecode(&bytecode).map_err(|source| ContractLoadError::InvalidBytecode {
+        path: artifact_path,
+        source,
+    })?;
+    Ok(bytecode)
+}
+
+/// Reads bytecode from the path RELATIVE to the ZKSYNC_HOME environment variable, linking it with the specified libraries.
+/// `links` are keyed by library placeholder IDs, i.e. the 34 hex chars between `__$` and `$__` in the bytecode
+/// (as reported by [`ContractLoadError::UnlinkedLibraries`]).
+pub fn read_bytecode_linked(
+    relative_path: impl AsRef<Path>,
+    links: &HashMap<String, Address>,
+) -> Vec<u8> {
+    try_read_bytecode_linked(relative_path, links).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Fallible version of [`read_bytecode_linked()`]. Returns [`ContractLoadError::UnlinkedLibraries`]
+/// if the bytecode contains placeholders for libraries missing from `links`.
+pub fn try_read_bytecode_linked(
+    relative_path: impl AsRef<Path>,
+    links: &HashMap<String, Address>,
+) -> Result<Vec<u8>, ContractLoadError> {
+    let artifact_path = zksync_home().join(relative_path);
+    let artifact = try_read_file_to_json_value(&artifact_path)?;
+    bytecode_from_artifact_linked(&artifact, links).map_err(|err| err.with_path(artifact_path))
+}
+
+/// Reads bytecode from the raw artifact JSON, e.g. embedded into the binary via `include_bytes!`.
+/// Supports the same bytecode formats as [`read_bytecode_from_path()`].
+///
+/// # Panics
+///
+/// Panics if the artifact is not valid JSON or doesn't contain valid bytecode.
+pub fn read_bytecode_from_slice(bytes: &[u8]) -> Vec<u8> {
+    let artifact: serde_json::Value = serde_json::from_slice(bytes)
+        .unwrap_or_else(|err| panic!("Failed to parse contract artifact: {}", err));
+    bytecode_from_artifact(&artifact)
+        .unwrap_or_else(|err| panic!("Failed to read bytecode from artifact: {}", err))
+}
+
+/// Reads bytecode from the path RELATIVE to the ZKSYNC_HOME environment variable and checks
+/// that its hash is contained in the `known` code hashes. Returns [`ContractLoadError::UnknownCode`] if it's not,
+/// and [`ContractLoadError::InvalidBytecode`] if the bytecode cannot be hashed.
+pub fn read_bytecode_if_known(
+    relative_path: impl AsRef<Path>,
+    known: &HashSet<H256>,
+) -> Result<Vec<u8>, ContractLoadError> {
+    let bytecode = try_read_valid_bytecode(&relative_path)?;
+    // Validated bytecode can always be hashed.
+    let hash = hash_bytecode(&bytecode);
+    if known.contains(&hash) {
+        Ok(bytecode)
+    } else {
+        Err(ContractLoadError::UnknownCode {
+            path: zksync_home().join(relative_path),
+            hash,
+        })
+    }
+}
+
+/// Error returned by [`read_bytecode_with_timeout()`] and [`read_bytecode_with_timeout_async()`].
+#[derive(Debug)]
+pub enum ReadBytecodeError {
+    /// Reading bytecode took too long (e.g., because of a hung network filesystem).
+    Timeout {
+        /// Path the bytecode was read from, relative to ZKSYNC_HOME.
+        path: PathBuf,
+        timeout: Duration,
+    },
+    /// Bytecode was read in time, but reading has failed (e.g., the artifact is missing or malformed).
+    Load(ContractLoadError),
+}
+
+impl fmt::Display for ReadBytecodeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { path, timeout } => write!(
+                formatter,
+                "timed out reading bytecode from {:?} after {:?}",
+                path, timeout
+            ),
+            Self::Load(err) => fmt::Display::fmt(err, formatter),
+        }
+    }
+}
+
+impl error::Error for ReadBytecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Timeout { .. } => None,
+            Self::Load(err) => Some(err),
+        }
+    }
+}
+
+/// Same as [`try_read_bytecode()`], but gives up if reading takes longer than `timeout`.
+///
+/// Reading is performed on a separate thread. On timeout, the thread is detached: it's neither joined nor cancelled,
+/// and keeps running in the background until the read returns (which may be never for a hung filesystem).
+pub fn read_bytecode_with_timeout(
+    relative_path: impl AsRef<Path>,
+    timeout: Duration,
+) -> Result<Vec<u8>, ReadBytecodeError> {
+    let path = relative_path.as_ref().to_path_buf();
+    let (sender, receiver) = mpsc::channel();
+    let thread_path = path.clone();
+    let handle = thread::spawn(move || {
+        sender.send(try_read_bytecode(thread_path)).ok();
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result.map_err(ReadBytecodeError::Load),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(ReadBytecodeError::Timeout { path, timeout }),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            // The reading thread has panicked; propagate the panic to the caller.
+            let panic = handle
+                .join()
+                .expect_err("reading thread finished without sending bytecode");
+            panic::resume_unwind(panic)
+        }
+    }
+}
+
+/// Async version of [`read_bytecode_with_timeout()`]. Reading is performed on a blocking Tokio task; similarly
+/// to the sync version, the task is detached on timeout.
+pub async fn read_bytecode_with_timeout_async(
+    relative_path: impl AsRef<Path>,
+    timeout: Duration,
+) -> Result<Vec<u8>, ReadBytecodeError> {
+    let path = relative_path.as_ref().to_path_buf();
+    let task_path = path.clone();
+    let task = tokio::task::spawn_blocking(move || try_read_bytecode(task_path));
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(result)) => result.map_err(ReadBytecodeError::Load),
+        Ok(Err(err)) => panic::resume_unwind(err.into_panic()),
+        Err(_) => Err(ReadBytecodeError::Timeout { path, timeout }),
+    }
+}
+
+/// Async version of [`try_load_contract()`]. Reading and parsing the artifact is performed on a blocking Tokio task,
+/// so that it doesn't block the async runtime.
+pub async fn load_contract_async(path: impl AsRef<Path>) -> Result<Contract, ContractLoadError> {
+    let path = path.as_ref().to_path_buf();
+    let task = tokio::task::spawn_blocking(move || try_load_contract(path));
+    task.await
+        .unwrap_or_else(|err| panic::resume_unwind(err.into_panic()))
+}
+
+/// Async version of [`try_read_bytecode()`]. Reading and parsing the artifact is performed on a blocking Tokio task,
+/// so that it doesn't block the async runtime.
+pub async fn read_bytecode_async(
+    relative_path: impl AsRef<Path>,
+) -> Result<Vec<u8>, ContractLoadError> {
+    let path = relative_path.as_ref().to_path_buf();
+    let task = tokio::task::spawn_blocking(move || try_read_bytecode(path));
+    task.await
+        .unwrap_or_else(|err| panic::resume_unwind(err.into_panic()))
 }
 
 pub fn default_erc20_bytecode() -> Vec<u8> {
@@ -195,12 +1055,11 @@ pub struct SystemContractsRepo {
 }
 
 impl SystemContractsRepo {
-    /// Returns the default system contracts repository with directory based on the ZKSYNC_HOME environment variable.
+    /// Returns the default system contracts repository with directory based on the ZKSYNC_HOME environment variable
+    /// (or the `SYS_CONTRACTS_ARTIFACTS_DIR` override; see [`system_contracts_dir()`]).
     pub fn from_env() -> Self {
-        let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-        let zksync_home = PathBuf::from(zksync_home);
         SystemContractsRepo {
-            root: zksync_home.join("contracts/system-contracts"),
+            root: system_contracts_dir().to_path_buf(),
         }
     }
     pub fn read_sys_contract_bytecode(
@@ -222,13 +1081,37 @@ impl SystemContractsRepo {
     }
 }
 
-pub fn read_bootloader_code(bootloader_type: &str) -> Vec<u8> {
-    read_zbin_bytecode(format!(
-        "contracts/system-contracts/bootloader/build/artifacts/{}.yul/{}.yul.zbin",
+fn bootloader_zbin_path(bootloader_type: &str) -> PathBuf {
+    system_contracts_dir().join(format!(
+        "bootloader/build/artifacts/{}.yul/{}.yul.zbin",
         bootloader_type, bootloader_type
     ))
 }
 
+/// Reads the code of the specified bootloader from the system contracts directory (see [`system_contracts_dir()`]).
+pub fn read_bootloader_code(bootloader_type: &str) -> Vec<u8> {
+    read_zbin_bytecode_from_path(bootloader_zbin_path(bootloader_type))
+}
+
+/// Returns the length of the bootloader code in 32-byte words. Only reads file metadata, so it's cheaper
+/// than loading the code via [`read_bootloader_code()`].
+///
+/// # Panics
+///
+/// Panics if the file metadata cannot be read, or if the code length is not divisible by 32.
+pub fn bootloader_code_len_words(bootloader_type: &str) -> usize {
+    let bytecode_path = bootloader_zbin_path(bootloader_type);
+    let metadata = fs::metadata(&bytecode_path)
+        .unwrap_or_else(|err| panic!("Can't read .zbin bytecode at {:?}: {}", bytecode_path, err));
+    let byte_len = metadata.len() as usize;
+    assert!(
+        byte_len % 32 == 0,
+        ".zbin bytecode at {:?} has length not divisible by 32",
+        bytecode_path
+    );
+    byte_len / 32
+}
+
 pub fn read_proved_batch_bootloader_bytecode() -> Vec<u8> {
     read_bootloader_code("proved_batch")
 }
@@ -253,8 +1136,7 @@ pub fn get_loadnext_test_contract_bytecode(file_name: &str, contract_name: &str)
 
 /// Reads zbin bytecode from a given path, relative to ZKSYNC_HOME.
 pub fn read_zbin_bytecode(relative_zbin_path: impl AsRef<Path>) -> Vec<u8> {
-    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
-    let bytecode_path = Path::new(&zksync_home).join(relative_zbin_path);
+    let bytecode_path = zksync_home().join(relative_zbin_path);
     read_zbin_bytecode_from_path(bytecode_path)
 }
 
@@ -263,6 +1145,45 @@ pub fn read_zbin_bytecode_from_path(bytecode_path: PathBuf) -> Vec<u8> {
     fs::read(&bytecode_path)
         .unwrap_or_else(|err| panic!("Can't read .zbin bytecode at {:?}: {}", bytecode_path, err))
 }
+
+/// Lazily reads zbin bytecode from a given path as a sequence of 32-byte big-endian words.
+/// Unlike [`read_zbin_bytecode_from_path()`], never holds the entire bytecode in memory,
+/// so it should be preferred for large artifacts consumed word by word.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read, or if its length is not divisible by 32.
+pub fn read_zbin_bytecode_words_streaming(
+    bytecode_path: impl AsRef<Path>,
+) -> impl Iterator<Item = U256> {
+    let bytecode_path = bytecode_path.as_ref().to_path_buf();
+    let file = File::open(&bytecode_path)
+        .unwrap_or_else(|err| panic!("Can't open .zbin bytecode at {:?}: {}", bytecode_path, err));
+    let mut reader = BufReader::new(file);
+
+    std::iter::from_fn(move || {
+        let mut word = [0_u8; 32];
+        let mut filled = 0;
+        while filled < word.len() {
+            match reader.read(&mut word[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    panic!("Can't read .zbin bytecode at {:?}: {}", bytecode_path, err)
+                }
+            }
+        }
+        match filled {
+            0 => None,
+            32 => Some(U256::from_big_endian(&word)),
+            _ => panic!(
+                ".zbin bytecode at {:?} has length not divisible by 32",
+                bytecode_path
+            ),
+        }
+    })
+}
 /// Hash of code and code which consists of 32 bytes words
 #[derive(Debug, Clone)]
 pub struct SystemContractCode {
@@ -289,42 +1210,38 @@ impl PartialEq for BaseSystemContracts {
     }
 }
 
-pub static PLAYGROUND_BLOCK_BOOTLOADER_CODE: Lazy<SystemContractCode> = Lazy::new(|| {
-    let bytecode = read_playground_batch_bootloader_bytecode();
+/// Hashes the `bytecode` and splits it into 32-byte words.
+pub fn system_contract_code_from_bytecode(bytecode: Vec<u8>) -> SystemContractCode {
     let hash = hash_bytecode(&bytecode);
-
     SystemContractCode {
         code: bytes_to_be_words(bytecode),
         hash,
     }
-});
+}
 
-pub static ESTIMATE_FEE_BLOCK_CODE: Lazy<SystemContractCode> = Lazy::new(|| {
-    let bytecode = read_bootloader_code("fee_estimate");
-    let hash = hash_bytecode(&bytecode);
+/// Reads system contract code from a .zbin file at the specified path (relative to ZKSYNC_HOME, unless absolute).
+/// Unlike statics like [`PLAYGROUND_BLOCK_BOOTLOADER_CODE`], returns an error instead of panicking, so it can be used
+/// to check that all required artifacts are present, e.g. on node startup.
+pub fn try_read_system_contract_code(
+    relative_zbin_path: impl AsRef<Path>,
+) -> Result<SystemContractCode, ContractLoadError> {
+    let bytecode_path = zksync_home().join(relative_zbin_path);
+    let bytecode =
+        fs::read(&bytecode_path).map_err(|source| ContractLoadError::io(bytecode_path, source))?;
+    Ok(system_contract_code_from_bytecode(bytecode))
+}
 
-    SystemContractCode {
-        code: bytes_to_be_words(bytecode),
-        hash,
-    }
-});
+pub static PLAYGROUND_BLOCK_BOOTLOADER_CODE: Lazy<SystemContractCode> =
+    Lazy::new(|| system_contract_code_from_bytecode(read_playground_batch_bootloader_bytecode()));
+
+pub static ESTIMATE_FEE_BLOCK_CODE: Lazy<SystemContractCode> =
+    Lazy::new(|| system_contract_code_from_bytecode(read_bootloader_code("fee_estimate")));
 
 impl BaseSystemContracts {
     fn load_with_bootloader(bootloader_bytecode: Vec<u8>) -> Self {
-        let hash = hash_bytecode(&bootloader_bytecode);
-
-        let bootloader = SystemContractCode {
-            code: bytes_to_be_words(bootloader_bytecode),
-            hash,
-        };
-
+        let bootloader = system_contract_code_from_bytecode(bootloader_bytecode);
         let bytecode = read_sys_contract_bytecode("", "DefaultAccount", ContractLanguage::Sol);
-        let hash = hash_bytecode(&bytecode);
-
-        let default_aa = SystemContractCode {
-            code: bytes_to_be_words(bytecode),
-            hash,
-        };
+        let default_aa = system_contract_code_from_bytecode(bytecode);
 
         BaseSystemContracts {
             bootloader,
@@ -889,3 +1806,768 @@ pub static PRE_BOOJUM_GET_VK_FUNCTION: Lazy<Function> = Lazy::new(|| {
     }"#;
     serde_json::from_str(abi).unwrap()
 });
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn write_artifact(json: &serde_json::Value) -> tempfile::NamedTempFile {
+        let mut artifact_file = tempfile::NamedTempFile::new().unwrap();
+        serde_json::to_writer(&mut artifact_file, json).unwrap();
+        artifact_file.flush().unwrap();
+        artifact_file
+    }
+
+    #[test]
+    fn cached_contract_is_loaded_from_disk_once() {
+        let functions: Vec<_> = (0..1_000)
+            .map(|i| {
+                serde_json::json!({
+                    "type": "function",
+                    "name": format!("function{}", i),
+                    "inputs": [{ "name": "value", "type": "uint256" }],
+                    "outputs": [{ "name": "", "type": "bytes32" }],
+                    "stateMutability": "view",
+                })
+            })
+            .collect();
+        let artifact = serde_json::json!({ "abi": functions });
+        let artifact_file = write_artifact(&artifact);
+
+        let contract = load_contract_cached(artifact_file.path());
+        assert_eq!(contract.functions().count(), 1_000);
+
+        // Remove the artifact to check that the second load doesn't touch the disk.
+        let artifact_path = artifact_file.path().to_owned();
+        artifact_file.close().unwrap();
+        let cached_contract = load_contract_cached(&artifact_path);
+        assert!(Arc::ptr_eq(&contract, &cached_contract));
+
+        // Uncached loads must still read the artifact from disk.
+        let err = try_load_contract(&artifact_path).unwrap_err();
+        assert!(matches!(err, ContractLoadError::NotFound { .. }), "{}", err);
+    }
+
+    #[test]
+    fn well_known_contracts_are_loaded_from_disk_once() {
+        let artifact = serde_json::json!({
+            "abi": [{
+                "type": "function",
+                "name": "foo",
+                "inputs": [],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }]
+        });
+        let artifact_file = write_artifact(&artifact);
+        let contract = cached_contract(artifact_file.path());
+        assert!(contract.function("foo").is_ok());
+
+        let artifact_path = artifact_file.path().to_owned();
+        artifact_file.close().unwrap();
+        let reloaded_contract = cached_contract(&artifact_path);
+        assert_eq!(reloaded_contract, contract);
+
+        // Public loaders share the cache, so they return equal ABIs on repeated calls.
+        let contract = zksync_contract();
+        let cached = load_contract_cached(ZKSYNC_CONTRACT_FILE);
+        assert_eq!(contract, *cached);
+        assert_eq!(zksync_contract(), contract);
+    }
+
+    #[test]
+    fn event_signature_in_zksync_contract() {
+        let contract = zksync_contract();
+        let expected = ethabi::long_signature(
+            "BlockCommit",
+            &[
+                ParamType::Uint(256),
+                ParamType::FixedBytes(32),
+                ParamType::FixedBytes(32),
+            ],
+        );
+        assert_eq!(event_signature(&contract, "BlockCommit"), Some(expected));
+        assert_eq!(event_signature(&contract, "NonExistingEvent"), None);
+    }
+
+    #[test]
+    fn function_selector_with_overloads() {
+        let contract = load_contract_from_slice(
+            br#"{
+                "abi": [{
+                    "type": "function",
+                    "name": "transfer",
+                    "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }, {
+                    "type": "function",
+                    "name": "safeTransferFrom",
+                    "inputs": [
+                        { "name": "from", "type": "address" },
+                        { "name": "to", "type": "address" },
+                        { "name": "tokenId", "type": "uint256" }
+                    ],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }, {
+                    "type": "function",
+                    "name": "safeTransferFrom",
+                    "inputs": [
+                        { "name": "from", "type": "address" },
+                        { "name": "to", "type": "address" },
+                        { "name": "tokenId", "type": "uint256" },
+                        { "name": "data", "type": "bytes" }
+                    ],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }]
+            }"#,
+        );
+
+        let transfer_selector = [0xa9, 0x05, 0x9c, 0xbb];
+        assert_eq!(
+            function_selector(&contract, "transfer", None),
+            Some(transfer_selector)
+        );
+        assert_eq!(
+            function_selector(&contract, "transfer", Some(2)),
+            Some(transfer_selector)
+        );
+        assert_eq!(function_selector(&contract, "transfer", Some(3)), None);
+        assert_eq!(function_selector(&contract, "transferFrom", None), None);
+
+        assert_eq!(function_selector(&contract, "safeTransferFrom", None), None);
+        assert_eq!(
+            function_selector(&contract, "safeTransferFrom", Some(3)),
+            Some([0x42, 0x84, 0x2e, 0x0e])
+        );
+        assert_eq!(
+            function_selector(&contract, "safeTransferFrom", Some(4)),
+            Some([0xb8, 0x8d, 0x4f, 0xde])
+        );
+    }
+
+    #[test]
+    fn listing_selectors_and_event_topics() {
+        let contract = load_contract_from_slice(
+            br#"{
+                "abi": [{
+                    "type": "function",
+                    "name": "transfer",
+                    "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }, {
+                    "type": "function",
+                    "name": "safeTransferFrom",
+                    "inputs": [
+                        { "name": "from", "type": "address" },
+                        { "name": "to", "type": "address" },
+                        { "name": "tokenId", "type": "uint256" }
+                    ],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }, {
+                    "type": "function",
+                    "name": "safeTransferFrom",
+                    "inputs": [
+                        { "name": "from", "type": "address" },
+                        { "name": "to", "type": "address" },
+                        { "name": "tokenId", "type": "uint256" },
+                        { "name": "data", "type": "bytes" }
+                    ],
+                    "outputs": [],
+                    "stateMutability": "nonpayable"
+                }, {
+                    "type": "event",
+                    "name": "Transfer",
+                    "inputs": [
+                        { "name": "from", "type": "address", "indexed": true },
+                        { "name": "to", "type": "address", "indexed": true },
+                        { "name": "value", "type": "uint256", "indexed": false }
+                    ],
+                    "anonymous": false
+                }]
+            }"#,
+        );
+
+        let selectors = contract_selectors(&contract);
+        assert_eq!(
+            selectors,
+            [
+                (
+                    "safeTransferFrom(address,address,uint256)".to_owned(),
+                    [0x42, 0x84, 0x2e, 0x0e]
+                ),
+                (
+                    "safeTransferFrom(address,address,uint256,bytes)".to_owned(),
+                    [0xb8, 0x8d, 0x4f, 0xde]
+                ),
+                ("transfer".to_owned(), [0xa9, 0x05, 0x9c, 0xbb]),
+            ]
+        );
+
+        let topics = contract_event_topics(&contract);
+        let expected_topic = ethabi::long_signature(
+            "Transfer",
+            &[ParamType::Address, ParamType::Address, ParamType::Uint(256)],
+        );
+        assert_eq!(topics, [("Transfer".to_owned(), expected_topic)]);
+    }
+
+    #[test]
+    fn loading_contract_from_slice() {
+        let artifact = br#"{
+            "abi": [{
+                "type": "function",
+                "name": "transfer",
+                "inputs": [{ "name": "to", "type": "address" }, { "name": "amount", "type": "uint256" }],
+                "outputs": [{ "name": "", "type": "bool" }],
+                "stateMutability": "nonpayable"
+            }],
+            "bytecode": "0x0102ff"
+        }"#;
+
+        let contract = load_contract_from_slice(artifact);
+        let transfer = contract.function("transfer").unwrap();
+        assert_eq!(transfer.short_signature(), [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(read_bytecode_from_slice(artifact), [1, 2, 255]);
+
+        let artifact = br#"{ "bytecode": [1, 2, 255] }"#;
+        assert_eq!(read_bytecode_from_slice(artifact), [1, 2, 255]);
+    }
+
+    #[test]
+    fn reading_bytecode_from_foundry_and_hardhat_artifacts() {
+        let foundry_artifact = br#"{
+            "abi": [],
+            "bytecode": { "object": "0x0102ff", "linkReferences": {} },
+            "deployedBytecode": { "object": "0x02ff", "linkReferences": {} }
+        }"#;
+        assert_eq!(read_bytecode_from_slice(foundry_artifact), [1, 2, 255]);
+
+        let hardhat_artifact = br#"{
+            "_format": "hh-sol-artifact-1",
+            "abi": [],
+            "deployedBytecode": "0x0102ff"
+        }"#;
+        assert_eq!(read_bytecode_from_slice(hardhat_artifact), [1, 2, 255]);
+
+        let artifact = serde_json::json!({ "abi": [], "contractName": "Test" });
+        let err = bytecode_from_artifact(&artifact).unwrap_err();
+        let err = err.with_path("Test.json".into()).to_string();
+        assert!(err.contains("present keys: [abi, contractName]"), "{}", err);
+    }
+
+    #[test]
+    fn linking_libraries_in_bytecode() {
+        const MATH_LIB: &str = "7f4a6a2f1b2c3d4e5f60718293a4b5c6d7";
+        const STRINGS_LIB: &str = "0123456789abcdef0123456789abcdef01";
+
+        let bytecode = format!(
+            "0x01__${}$__02__${}$__03__${}$__",
+            MATH_LIB, STRINGS_LIB, MATH_LIB
+        );
+        let artifact = serde_json::json!({ "bytecode": bytecode });
+
+        let err = bytecode_from_artifact(&artifact).unwrap_err();
+        assert!(
+            matches!(&err, ArtifactFieldError::UnlinkedLibraries(libraries) if libraries == &[MATH_LIB, STRINGS_LIB]),
+            "{:?}",
+            err
+        );
+
+        let mut links = HashMap::new();
+        links.insert(MATH_LIB.to_owned(), Address::repeat_byte(0xaa));
+        let err = bytecode_from_artifact_linked(&artifact, &links).unwrap_err();
+        assert!(
+            matches!(&err, ArtifactFieldError::UnlinkedLibraries(libraries) if libraries == &[STRINGS_LIB]),
+            "{:?}",
+            err
+        );
+
+        links.insert(STRINGS_LIB.to_owned(), Address::repeat_byte(0xbb));
+        let bytecode = bytecode_from_artifact_linked(&artifact, &links).unwrap();
+        let mut expected = vec![1];
+        expected.extend([0xaa; 20].iter());
+        expected.push(2);
+        expected.extend([0xbb; 20].iter());
+        expected.push(3);
+        expected.extend([0xaa; 20].iter());
+        assert_eq!(bytecode, expected);
+    }
+
+    #[test]
+    fn building_test_contract() {
+        let artifact = serde_json::json!({
+            "abi": [{
+                "type": "function",
+                "name": "foo",
+                "inputs": [],
+                "outputs": [],
+                "stateMutability": "nonpayable"
+            }],
+            "bytecode": "0x0102ff"
+        });
+        let artifact_file = write_artifact(&artifact);
+
+        let contract = TestContract::from_artifact(artifact_file.path());
+        assert_eq!(contract.bytecode, [1, 2, 255]);
+        assert!(contract.contract.function("foo").is_ok());
+        assert!(contract.factory_deps.is_empty());
+
+        let contract = TestContract::builder(vec![1, 2])
+            .with_abi(contract.contract)
+            .add_factory_dep(vec![3; 32])
+            .add_factory_dep(vec![4; 64])
+            .build();
+        assert_eq!(contract.bytecode, [1, 2]);
+        assert!(contract.contract.function("foo").is_ok());
+        assert_eq!(contract.factory_deps, [vec![3; 32], vec![4; 64]]);
+        assert_eq!(contract.factory_deps_total_bytes(), 98);
+    }
+
+    #[test]
+    fn deduplicating_factory_deps() {
+        let contract = TestContract::builder(vec![1, 2])
+            .add_factory_dep(vec![3; 32])
+            .add_factory_dep(vec![4; 96])
+            .add_factory_dep(vec![3; 32])
+            .build()
+            .with_deduplicated_factory_deps();
+        assert_eq!(contract.factory_deps, [vec![3; 32], vec![4; 96]]);
+    }
+
+    #[tokio::test]
+    async fn loading_contract_async() {
+        let artifact = serde_json::json!({ "abi": [], "bytecode": "0x0102ff" });
+        let artifact_file = write_artifact(&artifact);
+
+        let contract = load_contract_async(artifact_file.path()).await.unwrap();
+        assert_eq!(contract.functions().count(), 0);
+        let bytecode = read_bytecode_async(artifact_file.path()).await.unwrap();
+        assert_eq!(bytecode, [1, 2, 255]);
+
+        let missing_path = artifact_file.path().with_extension("missing");
+        let err = read_bytecode_async(&missing_path).await.unwrap_err();
+        assert!(
+            matches!(err, ContractLoadError::NotFound { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    /// Creates a FIFO, reading from which blocks until it's opened for writing.
+    #[cfg(unix)]
+    fn create_fifo(dir: &tempfile::TempDir) -> PathBuf {
+        let fifo_path = dir.path().join("artifact.json");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fifo_path
+    }
+
+    #[test]
+    fn reading_bytecode_with_timeout() {
+        let artifact = serde_json::json!({ "bytecode": "0x0102ff" });
+        let artifact_file = write_artifact(&artifact);
+        let bytecode =
+            read_bytecode_with_timeout(artifact_file.path(), Duration::from_secs(10)).unwrap();
+        assert_eq!(bytecode, [1, 2, 255]);
+
+        let missing_path = artifact_file.path().with_extension("missing");
+        let err = read_bytecode_with_timeout(&missing_path, Duration::from_secs(10)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ReadBytecodeError::Load(ContractLoadError::NotFound { .. })
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reading_bytecode_with_timeout_times_out() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let fifo_path = create_fifo(&dir);
+        let timeout = Duration::from_millis(50);
+        let err = read_bytecode_with_timeout(&fifo_path, timeout).unwrap_err();
+        assert!(
+            matches!(&err, ReadBytecodeError::Timeout { path, timeout: t } if *path == fifo_path && *t == timeout),
+            "{:?}",
+            err
+        );
+        // Unblock the detached reading thread.
+        File::create(&fifo_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reading_bytecode_with_timeout_async() {
+        let artifact = serde_json::json!({ "bytecode": "0x0102ff" });
+        let artifact_file = write_artifact(&artifact);
+        let bytecode =
+            read_bytecode_with_timeout_async(artifact_file.path(), Duration::from_secs(10))
+                .await
+                .unwrap();
+        assert_eq!(bytecode, [1, 2, 255]);
+
+        let missing_path = artifact_file.path().with_extension("missing");
+        let err = read_bytecode_with_timeout_async(&missing_path, Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ReadBytecodeError::Load(ContractLoadError::NotFound { .. })
+            ),
+            "{:?}",
+            err
+        );
+
+        #[cfg(unix)]
+        {
+            let dir = tempfile::TempDir::new().unwrap();
+            let fifo_path = create_fifo(&dir);
+            let err = read_bytecode_with_timeout_async(&fifo_path, Duration::from_millis(50))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, ReadBytecodeError::Timeout { .. }),
+                "{:?}",
+                err
+            );
+            // Unblock the detached reading task; otherwise, the runtime will wait for it on shutdown.
+            File::create(&fifo_path).unwrap();
+        }
+    }
+
+    #[test]
+    fn reading_system_contract_code() {
+        let mut bytecode_file = tempfile::NamedTempFile::new().unwrap();
+        // Valid bytecode must have an odd number of words.
+        let bytecode = [[0_u8; 32], [1; 32], [2; 32]].concat();
+        bytecode_file.write_all(&bytecode).unwrap();
+        bytecode_file.flush().unwrap();
+
+        let code = try_read_system_contract_code(bytecode_file.path()).unwrap();
+        let expected_words = [
+            U256::zero(),
+            U256::from_big_endian(&[1; 32]),
+            U256::from_big_endian(&[2; 32]),
+        ];
+        assert_eq!(code.code, expected_words);
+        assert_eq!(code.hash, hash_bytecode(&bytecode));
+
+        let missing_path = bytecode_file.path().with_extension("missing");
+        let err = try_read_system_contract_code(&missing_path).unwrap_err();
+        assert!(
+            matches!(err, ContractLoadError::NotFound { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn validating_bytecode_in_artifact() {
+        let read = |bytecode: &[u8]| {
+            let artifact =
+                serde_json::json!({ "bytecode": format!("0x{}", hex::encode(bytecode)) });
+            let artifact_file = write_artifact(&artifact);
+            try_read_valid_bytecode(artifact_file.path())
+        };
+
+        assert_eq!(read(&[1; 32]).unwrap(), [1; 32]);
+        let err = read(&[1; 31]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractLoadError::InvalidBytecode {
+                    source: InvalidBytecodeError::BytecodeLengthIsNotDivisibleBy32,
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+        let err = read(&vec![1; 32 << 16]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ContractLoadError::InvalidBytecode {
+                    source: InvalidBytecodeError::BytecodeTooLong(..),
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn reading_known_bytecode() {
+        let bytecode = [1; 32];
+        let artifact = serde_json::json!({ "bytecode": format!("0x{}", hex::encode(bytecode)) });
+        let artifact_file = write_artifact(&artifact);
+
+        let known = HashSet::from([hash_bytecode(&bytecode)]);
+        assert_eq!(
+            read_bytecode_if_known(artifact_file.path(), &known).unwrap(),
+            bytecode
+        );
+        let err = read_bytecode_if_known(artifact_file.path(), &HashSet::new()).unwrap_err();
+        assert!(
+            matches!(err, ContractLoadError::UnknownCode { hash, .. } if hash == hash_bytecode(&bytecode)),
+            "{:?}",
+            err
+        );
+
+        let missing_path = artifact_file.path().with_extension("missing");
+        let err = read_bytecode_if_known(missing_path, &known).unwrap_err();
+        assert!(
+            matches!(err, ContractLoadError::NotFound { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn resolving_system_contracts_dir() {
+        assert_eq!(
+            resolve_system_contracts_dir(None),
+            zksync_home().join("contracts/system-contracts")
+        );
+        assert_eq!(
+            resolve_system_contracts_dir(Some(Path::new("/ci/system-contracts"))),
+            Path::new("/ci/system-contracts")
+        );
+        assert_eq!(
+            resolve_system_contracts_dir(Some(Path::new("build/system-contracts"))),
+            zksync_home().join("build/system-contracts")
+        );
+    }
+
+    #[test]
+    fn reading_bytecode_with_hash() {
+        let bytecode = [1_u8; 32];
+        let artifact = serde_json::json!({ "bytecode": format!("0x{}", hex::encode(bytecode)) });
+        let artifact_file = write_artifact(&artifact);
+
+        let (read_bytecode, hash) = read_bytecode_with_hash(artifact_file.path());
+        assert_eq!(read_bytecode, bytecode);
+        assert_eq!(hash, hash_bytecode(&bytecode));
+    }
+
+    #[test]
+    fn invalid_bytecode_in_artifact() {
+        let artifact = serde_json::json!({ "bytecode": [1, 256] });
+        let err = bytecode_from_artifact(&artifact).unwrap_err();
+        assert!(
+            matches!(err, ArtifactFieldError::Invalid("bytecode", _)),
+            "{:?}",
+            err
+        );
+
+        let artifact = serde_json::json!({ "bytecode": "0102" });
+        let err = bytecode_from_artifact(&artifact).unwrap_err();
+        assert!(
+            matches!(err, ArtifactFieldError::Invalid("bytecode", _)),
+            "{:?}",
+            err
+        );
+
+        let artifact = serde_json::json!({ "abi": [] });
+        let err = bytecode_from_artifact(&artifact).unwrap_err();
+        assert!(
+            matches!(err, ArtifactFieldError::Missing("bytecode", _)),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn decoding_custom_revert() {
+        let contract = load_contract_from_slice(
+            br#"{
+                "abi": [{
+                    "type": "error",
+                    "name": "InsufficientBalance",
+                    "inputs": [
+                        { "name": "available", "type": "uint256" },
+                        { "name": "required", "type": "uint256" }
+                    ]
+                }]
+            }"#,
+        );
+        let param_types = [ParamType::Uint(256), ParamType::Uint(256)];
+        let args = vec![Token::Uint(10.into()), Token::Uint(20.into())];
+        let mut revert_data = short_signature("InsufficientBalance", &param_types).to_vec();
+        revert_data.extend(ethabi::encode(&args));
+
+        assert_eq!(
+            decode_revert(&contract, &revert_data),
+            Some(DecodedRevert::Custom {
+                name: "InsufficientBalance".to_owned(),
+                args,
+            })
+        );
+
+        let mut revert_data = short_signature("Error", &[ParamType::String]).to_vec();
+        revert_data.extend(ethabi::encode(&[Token::String("oops".to_owned())]));
+        assert_eq!(
+            decode_revert(&contract, &revert_data),
+            Some(DecodedRevert::Error("oops".to_owned()))
+        );
+
+        // Unknown selector
+        let mut revert_data = short_signature("Unknown", &param_types).to_vec();
+        revert_data.extend(ethabi::encode(&[
+            Token::Uint(1.into()),
+            Token::Uint(2.into()),
+        ]));
+        assert_eq!(decode_revert(&contract, &revert_data), None);
+        // Known selector, but malformed params
+        let revert_data = short_signature("InsufficientBalance", &param_types);
+        assert_eq!(decode_revert(&contract, &revert_data), None);
+        assert_eq!(decode_revert(&contract, &[1, 2]), None);
+    }
+
+    #[test]
+    fn checking_abi_coverage() {
+        let contract = erc20_contract();
+        let transfer_selector = [0xa9, 0x05, 0x9c, 0xbb];
+        let missing_selector = [0xde, 0xad, 0xbe, 0xef];
+        let selectors = abi_selector_set(&contract);
+        assert!(selectors.contains(&transfer_selector), "{:?}", selectors);
+        assert!(!selectors.contains(&missing_selector), "{:?}", selectors);
+
+        let required = BTreeSet::from([transfer_selector]);
+        assert!(abi_covers(&contract, &required).is_empty());
+        let required = BTreeSet::from([transfer_selector, missing_selector]);
+        assert_eq!(abi_covers(&contract, &required), [missing_selector]);
+    }
+
+    #[test]
+    fn reading_zbin_bytecode_words_streaming() {
+        let mut bytecode_file = tempfile::NamedTempFile::new().unwrap();
+        let bytecode = [[0_u8; 32], [1; 32], [2; 32]].concat();
+        bytecode_file.write_all(&bytecode).unwrap();
+        bytecode_file.flush().unwrap();
+
+        let words: Vec<_> = read_zbin_bytecode_words_streaming(bytecode_file.path()).collect();
+        let expected_words = bytes_to_be_words(read_zbin_bytecode(bytecode_file.path()));
+        assert_eq!(words, expected_words);
+        assert_eq!(words.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "length not divisible by 32")]
+    fn reading_zbin_bytecode_words_streaming_with_invalid_length() {
+        let mut bytecode_file = tempfile::NamedTempFile::new().unwrap();
+        bytecode_file.write_all(&[1; 33]).unwrap();
+        bytecode_file.flush().unwrap();
+
+        read_zbin_bytecode_words_streaming(bytecode_file.path()).for_each(drop);
+    }
+
+    #[test]
+    fn bootloader_code_len_in_words() {
+        let code_len = read_bootloader_code("proved_batch").len();
+        assert_eq!(bootloader_code_len_words("proved_batch"), code_len / 32);
+    }
+
+    #[test]
+    fn loading_system_context_abi() {
+        let abi = SystemContextAbi::default();
+        let functions = [
+            (&abi.set_tx_origin, "setTxOrigin"),
+            (&abi.set_gas_price, "setGasPrice"),
+            (&abi.set_chain_id, "setChainId"),
+            (&abi.set_new_batch, "setNewBatch"),
+            (&abi.set_l2_block, "setL2Block"),
+        ];
+        for (function, name) in functions {
+            assert_eq!(function.name, name);
+            assert_eq!(abi.contract.function(name).unwrap(), function);
+        }
+        assert_eq!(abi.set_tx_origin.inputs.len(), 1);
+        assert_eq!(abi.set_tx_origin.inputs[0].kind, ParamType::Address);
+        assert_eq!(abi.set_chain_id.inputs[0].kind, ParamType::Uint(256));
+        assert_eq!(abi.contract, system_context_contract());
+    }
+
+    #[test]
+    fn loading_bootloader_utilities_contract() {
+        let contract = bootloader_utilities_contract();
+        let function = contract.function("getTransactionHashes").unwrap();
+        assert_eq!(function.outputs.len(), 2);
+
+        let functions = BootloaderUtilitiesFunctions::default();
+        assert_eq!(functions.get_transaction_hashes, *function);
+    }
+
+    #[test]
+    fn hashing_factory_deps() {
+        let deps = [vec![3; 32], vec![4; 96], vec![5; 160]];
+        let contract = deps
+            .iter()
+            .fold(TestContract::builder(vec![1; 32]), |builder, dep| {
+                builder.add_factory_dep(dep.clone())
+            })
+            .build();
+        let expected_hashes: Vec<_> = deps.iter().map(|dep| hash_bytecode(dep)).collect();
+        assert_eq!(contract.factory_dep_hashes(), expected_hashes);
+        assert_ne!(expected_hashes[0], expected_hashes[1]);
+    }
+
+    #[test]
+    fn loading_qualified_sys_contract() {
+        let path = sys_contract_artifact_path("SystemContractHelper", "ISystemContract");
+        assert!(
+            path.ends_with(
+                "solpp-generated-contracts/SystemContractHelper.sol/ISystemContract.json"
+            ),
+            "{:?}",
+            path
+        );
+
+        let contract = load_sys_contract_qualified("ContractDeployer", "ContractDeployer");
+        assert_eq!(contract, load_sys_contract("ContractDeployer"));
+        assert_eq!(contract, deployer_contract());
+    }
+
+    #[test]
+    #[should_panic(expected = "ContractDeployer.sol/NonExistingContract.json")]
+    fn loading_missing_qualified_sys_contract() {
+        load_sys_contract_qualified("ContractDeployer", "NonExistingContract");
+    }
+
+    #[test]
+    fn reading_linked_bytecode_from_artifact() {
+        const LIBRARY: &str = "7f4a6a2f1b2c3d4e5f60718293a4b5c6d7";
+
+        let artifact = serde_json::json!({ "bytecode": format!("0x01__${}$__02", LIBRARY) });
+        let artifact_file = write_artifact(&artifact);
+
+        let err = try_read_bytecode_linked(artifact_file.path(), &HashMap::new()).unwrap_err();
+        assert!(
+            matches!(&err, ContractLoadError::UnlinkedLibraries { libraries, .. } if libraries == &[LIBRARY]),
+            "{:?}",
+            err
+        );
+
+        let links = HashMap::from([(LIBRARY.to_owned(), Address::repeat_byte(0xaa))]);
+        let bytecode = try_read_bytecode_linked(artifact_file.path(), &links).unwrap();
+        let mut expected = vec![1];
+        expected.extend([0xaa; 20].iter());
+        expected.push(2);
+        assert_eq!(bytecode, expected);
+        assert_eq!(read_bytecode_linked(artifact_file.path(), &links), expected);
+    }
+}
@@ -86,6 +86,15 @@ mod tests {
                 virtual_blocks_per_miniblock: 1,
                 upload_witness_inputs_to_gcs: false,
                 enum_index_migration_chunk_size: Some(2_000),
+                revert_guard_selectors: Some(vec!["0x4e487b71".to_owned()]),
+                blocked_tx_selectors: Some(vec!["0xa9059cbb".to_owned(), "0x095ea7b3".to_owned()]),
+                fixed_cadence_txs_per_batch: None,
+                max_l2_to_l1_logs_per_batch: Some(512),
+                max_pubdata_per_batch: Some(100000),
+                log_seal_criteria_transitions: false,
+                use_timeout_seal_criterion: false,
+                disabled_seal_criteria: Some(vec!["tx_encoding_size".to_owned()]),
+                all_of_seal_criteria: None,
             },
             operations_manager: OperationsManagerConfig {
                 delay_interval: 100,
@@ -132,6 +141,11 @@ mod tests {
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+            CHAIN_STATE_KEEPER_REVERT_GUARD_SELECTORS="0x4e487b71"
+            CHAIN_STATE_KEEPER_BLOCKED_TX_SELECTORS="0xa9059cbb,0x095ea7b3"
+            CHAIN_STATE_KEEPER_MAX_L2_TO_L1_LOGS_PER_BATCH="512"
+            CHAIN_STATE_KEEPER_MAX_PUBDATA_PER_BATCH="100000"
+            CHAIN_STATE_KEEPER_DISABLED_SEAL_CRITERIA="tx_encoding_size"
             CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
             CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
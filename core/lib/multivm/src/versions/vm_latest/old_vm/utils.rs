@@ -152,11 +152,17 @@ pub(crate) fn dump_memory_page_using_fat_pointer<H: HistoryMode>(
     memory: &SimpleMemory<H>,
     fat_ptr: FatPointer,
 ) -> Vec<u8> {
+    let offset = fat_ptr.start.checked_add(fat_ptr.offset);
+    let length = fat_ptr.length.checked_sub(fat_ptr.offset);
+    let (Some(offset), Some(length)) = (offset, length) else {
+        tracing::warn!("Failed dumping memory using inconsistent fat pointer: {fat_ptr:?}");
+        return vec![];
+    };
     dump_memory_page_by_offset_and_length(
         memory,
         fat_ptr.memory_page,
-        (fat_ptr.start + fat_ptr.offset) as usize,
-        (fat_ptr.length - fat_ptr.offset) as usize,
+        offset as usize,
+        length as usize,
     )
 }
 
@@ -219,3 +225,37 @@ pub(crate) fn dump_memory_page_by_offset_and_length<H: HistoryMode>(
 
     dump
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_latest::{constants::BOOTLOADER_HEAP_PAGE, HistoryDisabled};
+
+    fn fat_pointer(start: u32, offset: u32, length: u32) -> FatPointer {
+        FatPointer {
+            offset,
+            memory_page: BOOTLOADER_HEAP_PAGE,
+            start,
+            length,
+        }
+    }
+
+    #[test]
+    fn dumping_memory_using_inconsistent_fat_pointer() {
+        let mut memory = SimpleMemory::<HistoryDisabled>::default();
+        memory.populate_page(
+            BOOTLOADER_HEAP_PAGE as usize,
+            vec![(0, U256::from(0x0102_0304_u64))],
+            Timestamp(0),
+        );
+
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(0, 28, 32));
+        assert_eq!(dump, [1, 2, 3, 4]);
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(0, 32, 32));
+        assert!(dump.is_empty());
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(0, 33, 32));
+        assert!(dump.is_empty());
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(u32::MAX, 1, 32));
+        assert!(dump.is_empty());
+    }
+}
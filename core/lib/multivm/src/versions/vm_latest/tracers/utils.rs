@@ -5,10 +5,7 @@ use zk_evm_1_4_0::{
         FarCallABI, FarCallForwardPageType, FatPointer, LogOpcode, Opcode, UMAOpcode,
     },
 };
-use zksync_system_constants::{
-    ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, KNOWN_CODES_STORAGE_ADDRESS,
-    L1_MESSENGER_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
-};
+use zksync_system_constants::{Precompile, KNOWN_CODES_STORAGE_ADDRESS, L1_MESSENGER_ADDRESS};
 use zksync_types::U256;
 use zksync_utils::u256_to_h256;
 
@@ -174,13 +171,11 @@ pub(crate) fn computational_gas_price(
         Opcode::Log(LogOpcode::PrecompileCall) => {
             let address = state.vm_local_state.callstack.current.this_address;
 
-            if address == KECCAK256_PRECOMPILE_ADDRESS
-                || address == SHA256_PRECOMPILE_ADDRESS
-                || address == ECRECOVER_PRECOMPILE_ADDRESS
-            {
-                data.src1_value.value.low_u32()
-            } else {
-                0
+            match Precompile::from_address(address) {
+                Some(Precompile::Keccak256 | Precompile::Sha256 | Precompile::Ecrecover) => {
+                    data.src1_value.value.low_u32()
+                }
+                _ => 0,
             }
         }
         _ => 0,
@@ -1,5 +1,6 @@
 //! Utility functions for vm
 use zksync_system_constants::MAX_GAS_PER_PUBDATA_BYTE;
+use zksync_types::U256;
 use zksync_utils::ceil_div;
 
 use crate::vm_latest::old_vm::utils::eth_price_per_pubdata_byte;
@@ -27,3 +28,10 @@ pub fn derive_base_fee_and_gas_per_pubdata(l1_gas_price: u64, fair_gas_price: u6
         base_fee_to_gas_per_pubdata(l1_gas_price, base_fee),
     )
 }
+
+/// Derives the gas per pubdata byte for the given gas prices. Prefer this to [`derive_base_fee_and_gas_per_pubdata()`]
+/// if the base fee is not needed.
+pub fn derive_gas_per_pubdata(l1_gas_price: u64, fair_gas_price: u64) -> U256 {
+    let (_, gas_per_pubdata) = derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_gas_price);
+    gas_per_pubdata.into()
+}
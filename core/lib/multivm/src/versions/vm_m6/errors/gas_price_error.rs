@@ -0,0 +1,20 @@
+use std::ops::RangeInclusive;
+
+/// Error returned by [`BlockContext::validate_gas_prices()`](crate::vm_m6::vm_with_bootloader::BlockContext::validate_gas_prices).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GasPriceError {
+    #[error("L1 gas price is zero")]
+    ZeroL1GasPrice,
+    #[error("fair L2 gas price is zero")]
+    ZeroFairL2GasPrice,
+    #[error("L1 gas price {price} is outside of the sane range {range:?}")]
+    L1GasPriceOutOfRange {
+        price: u64,
+        range: RangeInclusive<u64>,
+    },
+    #[error("fair L2 gas price {price} is outside of the sane range {range:?}")]
+    FairL2GasPriceOutOfRange {
+        price: u64,
+        range: RangeInclusive<u64>,
+    },
+}
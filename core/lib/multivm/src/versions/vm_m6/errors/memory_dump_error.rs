@@ -0,0 +1,15 @@
+/// Error returned by [`try_dump_memory_page_by_offset_and_length()`](crate::vm_m6::utils::try_dump_memory_page_by_offset_and_length)
+/// and [`try_dump_memory_page_using_fat_pointer()`](crate::vm_m6::utils::try_dump_memory_page_using_fat_pointer).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MemoryDumpError {
+    #[error("offset {0} is out of range of a memory page")]
+    OffsetOutOfRange(usize),
+    #[error("length {0} is out of range of a memory page")]
+    LengthOutOfRange(usize),
+    #[error("fat pointer with start {start}, offset {offset} and length {length} is inconsistent")]
+    InvalidFatPointer {
+        start: u32,
+        offset: u32,
+        length: u32,
+    },
+}
@@ -1,9 +1,15 @@
 mod bootloader_error;
+mod gas_price_error;
+mod memory_dump_error;
 mod tx_revert_reason;
+mod vm_execution_error;
 mod vm_revert_reason;
 
 pub(crate) use bootloader_error::BootloaderErrorCode;
+pub use gas_price_error::GasPriceError;
+pub use memory_dump_error::MemoryDumpError;
 pub use tx_revert_reason::TxRevertReason;
+pub use vm_execution_error::VmExecutionError;
 pub use vm_revert_reason::{
     VmRevertReason, VmRevertReasonParsingError, VmRevertReasonParsingResult,
 };
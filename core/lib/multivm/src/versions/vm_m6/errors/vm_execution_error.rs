@@ -0,0 +1,26 @@
+use std::convert::TryFrom;
+
+use zksync_types::Address;
+
+use crate::vm_m6::errors::{VmRevertReason, VmRevertReasonParsingError};
+
+/// Error returned by [`VmExecutionResult::into_result()`](crate::vm_m6::utils::VmExecutionResult::into_result).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VmExecutionError {
+    #[error("VM execution reverted with {} bytes of revert data", .0.len())]
+    Revert(Vec<u8>),
+    #[error("VM execution panicked")]
+    Panic,
+    #[error("VM execution most likely did not finish (contract {address:?}, pc {pc})")]
+    DidNotFinish { address: Address, pc: u16 },
+}
+
+impl VmExecutionError {
+    /// Decodes the revert reason if execution has reverted. Returns `None` for other errors.
+    pub fn revert_reason(&self) -> Option<Result<VmRevertReason, VmRevertReasonParsingError>> {
+        match self {
+            Self::Revert(data) => Some(VmRevertReason::try_from(data.as_slice())),
+            Self::Panic | Self::DidNotFinish { .. } => None,
+        }
+    }
+}
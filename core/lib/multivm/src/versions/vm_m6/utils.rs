@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
 use once_cell::sync::Lazy;
 use zk_evm_1_3_1::{
     aux_structures::{LogQuery, MemoryPage, Timestamp},
@@ -5,16 +7,25 @@ use zk_evm_1_3_1::{
     vm_state::PrimitiveValue,
     zkevm_opcode_defs::FatPointer,
 };
-use zksync_contracts::{read_zbin_bytecode, BaseSystemContracts};
+use zksync_contracts::{
+    decode_revert, zksync_home, BaseSystemContracts, DecodedRevert, SystemContractCode,
+    ESTIMATE_FEE_BLOCK_CODE,
+};
+use zksync_state::InMemoryStorage;
 use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
-use zksync_types::{Address, StorageLogQuery, H160, MAX_L2_TX_GAS_LIMIT, U256};
-use zksync_utils::h256_to_u256;
+use zksync_types::{Address, L2ChainId, StorageLogQuery, H160, H256, MAX_L2_TX_GAS_LIMIT, U256};
+use zksync_utils::{bytecode::hash_bytecode, h256_to_u256};
 
 use crate::{
     glue::GlueInto,
     vm_m6::{
-        history_recorder::HistoryMode, memory::SimpleMemory, oracles::tracer::PubdataSpentTracer,
-        storage::Storage, vm_with_bootloader::BlockContext, VmInstance,
+        errors::{MemoryDumpError, VmExecutionError},
+        history_recorder::HistoryMode,
+        memory::SimpleMemory,
+        oracles::tracer::PubdataSpentTracer,
+        storage::Storage,
+        vm_with_bootloader::BlockContext,
+        VmInstance,
     },
 };
 
@@ -31,6 +42,31 @@ pub const BLOCK_GAS_LIMIT: u32 =
     zk_evm_1_3_1::zkevm_opcode_defs::system_params::VM_INITIAL_FRAME_ERGS;
 pub const ETH_CALL_GAS_LIMIT: u32 = MAX_L2_TX_GAS_LIMIT as u32;
 
+/// Memory layout and limits of the VM, e.g. for diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VmLayoutConstants {
+    pub initial_timestamp: u32,
+    pub initial_memory_counter: u32,
+    pub initial_calldata_page: u32,
+    pub initial_base_page: u32,
+    pub entry_point_page: u32,
+    pub block_gas_limit: u32,
+    pub eth_call_gas_limit: u32,
+}
+
+/// Returns memory layout and limits used by the VM.
+pub fn vm_layout_constants() -> VmLayoutConstants {
+    VmLayoutConstants {
+        initial_timestamp: INITIAL_TIMESTAMP,
+        initial_memory_counter: INITIAL_MEMORY_COUNTER,
+        initial_calldata_page: INITIAL_CALLDATA_PAGE,
+        initial_base_page: INITIAL_BASE_PAGE,
+        entry_point_page: ENTRY_POINT_PAGE,
+        block_gas_limit: BLOCK_GAS_LIMIT,
+        eth_call_gas_limit: ETH_CALL_GAS_LIMIT,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum VmExecutionResult {
     Ok(Vec<u8>),
@@ -39,6 +75,56 @@ pub enum VmExecutionResult {
     MostLikelyDidNotFinish(Address, u16),
 }
 
+impl VmExecutionResult {
+    /// Returns data returned by the VM regardless of whether execution succeeded or reverted.
+    /// Returns an empty slice if the VM panicked or didn't finish.
+    pub fn return_data(&self) -> &[u8] {
+        match self {
+            Self::Ok(data) | Self::Revert(data) => data,
+            Self::Panic | Self::MostLikelyDidNotFinish(..) => &[],
+        }
+    }
+
+    /// Converts this result into a `Result`, so that it can be used with the `?` operator.
+    /// Returns data returned by the VM on success.
+    pub fn into_result(self) -> Result<Vec<u8>, VmExecutionError> {
+        match self {
+            Self::Ok(data) => Ok(data),
+            Self::Revert(data) => Err(VmExecutionError::Revert(data)),
+            Self::Panic => Err(VmExecutionError::Panic),
+            Self::MostLikelyDidNotFinish(address, pc) => {
+                Err(VmExecutionError::DidNotFinish { address, pc })
+            }
+        }
+    }
+}
+
+/// Decodes a standard `Error(string)` or `Panic(uint256)` revert, e.g. returned in [`VmExecutionResult::Revert`],
+/// into a human-readable message. Returns `None` if the revert data is not recognized.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    match decode_revert(&ethabi::Contract::default(), data)? {
+        DecodedRevert::Error(message) => Some(message),
+        DecodedRevert::Panic(code) => {
+            // See https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+            let description = match u64::try_from(code) {
+                Ok(0x00) => "generic compiler panic",
+                Ok(0x01) => "assertion failed",
+                Ok(0x11) => "arithmetic overflow or underflow",
+                Ok(0x12) => "division or modulo by zero",
+                Ok(0x21) => "invalid enum value",
+                Ok(0x22) => "incorrectly encoded storage byte array",
+                Ok(0x31) => "pop() on an empty array",
+                Ok(0x32) => "array index out of bounds",
+                Ok(0x41) => "too much memory allocated",
+                Ok(0x51) => "call to a zero-initialized internal function",
+                _ => "unknown panic code",
+            };
+            Some(format!("panic {code:#x}: {description}"))
+        }
+        DecodedRevert::Custom { .. } => None, // cannot happen since the ABI has no custom errors
+    }
+}
+
 pub const fn code_page_candidate_from_base(base: MemoryPage) -> MemoryPage {
     MemoryPage(base.0)
 }
@@ -55,6 +141,23 @@ pub const fn aux_heap_page_from_base(base: MemoryPage) -> MemoryPage {
     MemoryPage(base.0 + 3)
 }
 
+/// Converts a byte offset in a memory page into the index of the 32-byte word containing the byte
+/// and the offset of the byte within this word.
+///
+/// # Panics
+///
+/// Panics if the word index doesn't fit into `u32`. This cannot happen for offsets within a memory page
+/// (e.g., ones that passed bounds checks in dumping functions).
+pub fn byte_offset_to_word(offset: usize) -> (u32, usize) {
+    let word_index = u32::try_from(offset / 32).expect("word index doesn't fit into `u32`");
+    (word_index, offset % 32)
+}
+
+/// Converts the index of a 32-byte word in a memory page into the byte offset of the word start.
+pub fn word_to_byte_offset(word: u32) -> usize {
+    word as usize * 32
+}
+
 pub(crate) fn dump_memory_page_using_primitive_value<H: HistoryMode>(
     memory: &SimpleMemory<H>,
     ptr: PrimitiveValue,
@@ -66,76 +169,158 @@ pub(crate) fn dump_memory_page_using_primitive_value<H: HistoryMode>(
     dump_memory_page_using_fat_pointer(memory, fat_ptr)
 }
 
+/// Dumps memory referenced by the fat pointer. Since fat pointers come from VM memory, they may be malformed;
+/// an empty dump is returned for an inconsistent pointer.
 pub(crate) fn dump_memory_page_using_fat_pointer<H: HistoryMode>(
     memory: &SimpleMemory<H>,
     fat_ptr: FatPointer,
 ) -> Vec<u8> {
-    dump_memory_page_by_offset_and_length(
+    try_dump_memory_page_using_fat_pointer(memory, fat_ptr).unwrap_or_else(|err| {
+        tracing::warn!("Failed dumping memory using fat pointer: {err}");
+        vec![]
+    })
+}
+
+/// Fallible version of [`dump_memory_page_using_fat_pointer()`].
+pub fn try_dump_memory_page_using_fat_pointer<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    fat_ptr: FatPointer,
+) -> Result<Vec<u8>, MemoryDumpError> {
+    let invalid_pointer = || MemoryDumpError::InvalidFatPointer {
+        start: fat_ptr.start,
+        offset: fat_ptr.offset,
+        length: fat_ptr.length,
+    };
+    let offset = fat_ptr
+        .start
+        .checked_add(fat_ptr.offset)
+        .ok_or_else(invalid_pointer)?;
+    let length = fat_ptr
+        .length
+        .checked_sub(fat_ptr.offset)
+        .ok_or_else(invalid_pointer)?;
+    try_dump_memory_page(
         memory,
-        fat_ptr.memory_page,
-        (fat_ptr.start + fat_ptr.offset) as usize,
-        (fat_ptr.length - fat_ptr.offset) as usize,
+        MemoryPage(fat_ptr.memory_page),
+        offset as usize,
+        length as usize,
     )
 }
 
+/// Dumps `length` bytes starting from `offset` from the specified memory page.
+///
+/// # Panics
+///
+/// Panics if `offset` or `length` is out of range. Use [`try_dump_memory_page()`] if they are controlled
+/// by the executed code.
+pub fn dump_memory_page<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    page: MemoryPage,
+    offset: usize,
+    length: usize,
+) -> Vec<u8> {
+    dump_memory_page_by_offset_and_length(memory, page.0, offset, length)
+}
+
+/// Fallible version of [`dump_memory_page()`].
+pub fn try_dump_memory_page<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    page: MemoryPage,
+    offset: usize,
+    length: usize,
+) -> Result<Vec<u8>, MemoryDumpError> {
+    try_dump_memory_page_by_offset_and_length(memory, page.0, offset, length)
+}
+
 pub(crate) fn dump_memory_page_by_offset_and_length<H: HistoryMode>(
     memory: &SimpleMemory<H>,
     page: u32,
     offset: usize,
     length: usize,
 ) -> Vec<u8> {
-    assert!(offset < (1u32 << 24) as usize);
-    assert!(length < (1u32 << 24) as usize);
-    let mut dump = Vec::with_capacity(length);
-    if length == 0 {
-        return dump;
-    }
+    try_dump_memory_page_by_offset_and_length(memory, page, offset, length)
+        .unwrap_or_else(|err| panic!("{err}"))
+}
 
-    let first_word = offset / 32;
-    let end_byte = offset + length;
-    let mut last_word = end_byte / 32;
-    if end_byte % 32 != 0 {
-        last_word += 1;
-    }
+/// Fallible version of [`dump_memory_page_by_offset_and_length()`]. Should be used if `offset` and `length`
+/// are controlled by the executed code.
+pub fn try_dump_memory_page_by_offset_and_length<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    page: u32,
+    offset: usize,
+    length: usize,
+) -> Result<Vec<u8>, MemoryDumpError> {
+    check_dump_bounds(offset, length)?;
+    let mut dump = Vec::with_capacity(length);
+    dump_memory_page_into(memory, page, offset, length, &mut dump)
+        .expect("writing to `Vec` cannot fail");
+    Ok(dump)
+}
 
-    let unalignment = offset % 32;
+fn check_dump_bounds(offset: usize, length: usize) -> Result<(), MemoryDumpError> {
+    const MAX_OFFSET_OR_LENGTH: usize = 1 << 24;
 
-    let page_part =
-        memory.dump_page_content_as_u256_words(page, (first_word as u32)..(last_word as u32));
+    if offset >= MAX_OFFSET_OR_LENGTH {
+        return Err(MemoryDumpError::OffsetOutOfRange(offset));
+    }
+    if length >= MAX_OFFSET_OR_LENGTH {
+        return Err(MemoryDumpError::LengthOutOfRange(length));
+    }
+    Ok(())
+}
 
-    let mut is_first = true;
+/// Same as [`try_dump_memory_page_by_offset_and_length()`], but writes the dumped bytes to `out` word by word
+/// instead of collecting them into a `Vec`, e.g. to hash large memory regions without intermediate allocations.
+/// Out-of-range `offset` or `length` are reported as [`io::ErrorKind::InvalidInput`] errors wrapping [`MemoryDumpError`].
+pub fn dump_memory_page_into<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    page: u32,
+    offset: usize,
+    length: usize,
+    out: &mut impl io::Write,
+) -> io::Result<()> {
+    check_dump_bounds(offset, length)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    let (mut word_index, unalignment) = byte_offset_to_word(offset);
+    let mut word_bytes = [0_u8; 32];
+    let mut skipped = unalignment;
     let mut remaining = length;
-    for word in page_part.into_iter() {
-        let it = word.into_be_iter();
-        if is_first {
-            is_first = false;
-            let it = it.skip(unalignment);
-            for next in it {
-                if remaining > 0 {
-                    dump.push(next);
-                    remaining -= 1;
-                }
-            }
-        } else {
-            for next in it {
-                if remaining > 0 {
-                    dump.push(next);
-                    remaining -= 1;
-                }
-            }
-        }
+    while remaining > 0 {
+        let word = memory.read_slot(page as usize, word_index as usize).value;
+        word.to_big_endian(&mut word_bytes);
+        let chunk = &word_bytes[skipped..];
+        let chunk = &chunk[..chunk.len().min(remaining)];
+        out.write_all(chunk)?;
+
+        remaining -= chunk.len();
+        skipped = 0;
+        word_index += 1;
     }
+    Ok(())
+}
 
-    assert_eq!(
-        dump.len(),
-        length,
-        "tried to dump with offset {}, length {}, got a bytestring of length {}",
-        offset,
-        length,
-        dump.len()
-    );
+/// Same as [`try_dump_memory_page_by_offset_and_length()`], but dumps at most `max` bytes. Returns the dumped bytes
+/// and a flag whether they were truncated. Should be used to bound memory consumption when the length
+/// is controlled by the executed code (e.g., for return data).
+pub fn dump_memory_page_capped<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    page: u32,
+    offset: usize,
+    length: usize,
+    max: usize,
+) -> Result<(Vec<u8>, bool), MemoryDumpError> {
+    let was_truncated = length > max;
+    let dump = try_dump_memory_page_by_offset_and_length(memory, page, offset, length.min(max))?;
+    Ok((dump, was_truncated))
+}
 
-    dump
+/// Dumps the calldata of the entry point frame, i.e. the first `length` bytes of [`INITIAL_CALLDATA_PAGE`].
+pub fn dump_entry_point_calldata<H: HistoryMode>(
+    memory: &SimpleMemory<H>,
+    length: usize,
+) -> Vec<u8> {
+    dump_memory_page_by_offset_and_length(memory, INITIAL_CALLDATA_PAGE, 0, length)
 }
 
 pub trait FixedLengthIterator<'a, I: 'a, const N: usize>: Iterator<Item = I>
@@ -190,22 +375,91 @@ impl IntoFixedLengthByteIterator<32> for U256 {
     }
 }
 
+/// `H256` is stored in the big-endian order, so `into_be_iter()` iterates over its bytes as is.
+impl IntoFixedLengthByteIterator<32> for H256 {
+    type IntoIter = FixedBufferValueIterator<u8, 32>;
+    fn into_le_iter(self) -> Self::IntoIter {
+        let mut buffer = self.0;
+        buffer.reverse();
+
+        FixedBufferValueIterator {
+            iter: IntoIterator::into_iter(buffer),
+        }
+    }
+
+    fn into_be_iter(self) -> Self::IntoIter {
+        FixedBufferValueIterator {
+            iter: IntoIterator::into_iter(self.0),
+        }
+    }
+}
+
+/// `Address` is stored in the big-endian order, so `into_be_iter()` iterates over its bytes as is.
+impl IntoFixedLengthByteIterator<20> for Address {
+    type IntoIter = FixedBufferValueIterator<u8, 20>;
+    fn into_le_iter(self) -> Self::IntoIter {
+        let mut buffer = self.0;
+        buffer.reverse();
+
+        FixedBufferValueIterator {
+            iter: IntoIterator::into_iter(buffer),
+        }
+    }
+
+    fn into_be_iter(self) -> Self::IntoIter {
+        FixedBufferValueIterator {
+            iter: IntoIterator::into_iter(self.0),
+        }
+    }
+}
+
 /// Collects storage log queries where `log.log_query.timestamp >= from_timestamp`.
 /// Denote `n` to be the number of such queries, then it works in O(n).
 pub fn collect_storage_log_queries_after_timestamp(
     all_log_queries: &[StorageLogQuery],
     from_timestamp: Timestamp,
+) -> Vec<StorageLogQuery> {
+    collect_storage_log_queries_after_timestamp_filtered(
+        all_log_queries,
+        from_timestamp,
+        |_| true,
+        None,
+    )
+}
+
+/// Same as [`collect_storage_log_queries_after_timestamp()`], but only collects queries satisfying the `predicate`.
+/// If `limit` is specified, collects at most `limit` most recent matching queries, stopping the search
+/// once they are found. Queries are returned in the original order.
+pub fn collect_storage_log_queries_after_timestamp_filtered(
+    all_log_queries: &[StorageLogQuery],
+    from_timestamp: Timestamp,
+    predicate: impl Fn(&StorageLogQuery) -> bool,
+    limit: Option<usize>,
 ) -> Vec<StorageLogQuery> {
     let from_timestamp = from_timestamp.glue_into();
-    all_log_queries
+    let mut log_queries: Vec<_> = all_log_queries
         .iter()
         .rev()
         .take_while(|log_query| log_query.log_query.timestamp >= from_timestamp)
+        .filter(|log_query| predicate(log_query))
+        .take(limit.unwrap_or(usize::MAX))
         .cloned()
-        .collect::<Vec<StorageLogQuery>>()
-        .into_iter()
-        .rev()
-        .collect()
+        .collect();
+    log_queries.reverse();
+    log_queries
+}
+
+/// Same as [`collect_storage_log_queries_after_timestamp()`], but requires `sorted_log_queries` to be sorted
+/// by timestamp. Rather than copying queries, returns the suffix of `sorted_log_queries` with the matching queries.
+/// Works in O(log(sorted_log_queries.len())).
+pub fn collect_storage_log_queries_after_timestamp_sorted(
+    sorted_log_queries: &[StorageLogQuery],
+    from_timestamp: Timestamp,
+) -> &[StorageLogQuery] {
+    let from_timestamp = from_timestamp.glue_into();
+    let cut_index = sorted_log_queries
+        .partition_point(|log_query| log_query.log_query.timestamp < from_timestamp);
+    &sorted_log_queries[cut_index..]
 }
 
 /// Collects all log queries where `log_query.timestamp >= from_timestamp`.
@@ -214,15 +468,81 @@ pub fn collect_log_queries_after_timestamp(
     all_log_queries: &[LogQuery],
     from_timestamp: Timestamp,
 ) -> Vec<LogQuery> {
-    all_log_queries
+    collect_log_queries_after_timestamp_where(all_log_queries, from_timestamp, |_| true)
+}
+
+/// Same as [`collect_log_queries_after_timestamp()`], but only collects queries satisfying the `predicate`.
+/// Queries are filtered during the scan and are returned in the original order.
+pub fn collect_log_queries_after_timestamp_where(
+    all_log_queries: &[LogQuery],
+    from_timestamp: Timestamp,
+    predicate: impl Fn(&LogQuery) -> bool,
+) -> Vec<LogQuery> {
+    let mut log_queries: Vec<_> = all_log_queries
         .iter()
         .rev()
         .take_while(|log_query| log_query.timestamp >= from_timestamp)
+        .filter(|log_query| predicate(log_query))
         .cloned()
-        .collect::<Vec<LogQuery>>()
-        .into_iter()
-        .rev()
-        .collect()
+        .collect();
+    log_queries.reverse();
+    log_queries
+}
+
+/// Difference between storage log queries that two VM runs produced for the same storage slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageLogDiff {
+    /// The slot was accessed only in the first run.
+    OnlyInFirst(StorageLogQuery),
+    /// The slot was accessed only in the second run.
+    OnlyInSecond(StorageLogQuery),
+    /// The slot was accessed in both runs, but the values or the kind of access differ.
+    Mismatch {
+        first: StorageLogQuery,
+        second: StorageLogQuery,
+    },
+}
+
+/// Compares storage log queries produced by two VM runs (e.g., the same transaction executed by two VM builds).
+/// Queries are keyed by `(address, key)`; if a slot is accessed several times within a run, the last query
+/// is used. Timestamps are ignored, since they are not expected to match across runs.
+/// The returned diffs are sorted by `(address, key)`.
+pub fn diff_storage_log_queries(
+    a: &[StorageLogQuery],
+    b: &[StorageLogQuery],
+) -> Vec<StorageLogDiff> {
+    fn last_query_per_slot(
+        queries: &[StorageLogQuery],
+    ) -> BTreeMap<(Address, U256), StorageLogQuery> {
+        queries
+            .iter()
+            .map(|query| ((query.log_query.address, query.log_query.key), *query))
+            .collect()
+    }
+
+    let mut first = last_query_per_slot(a);
+    let second = last_query_per_slot(b);
+    let mut diffs = vec![];
+    for (slot, second) in second {
+        let Some(first) = first.remove(&slot) else {
+            diffs.push((slot, StorageLogDiff::OnlyInSecond(second)));
+            continue;
+        };
+        let is_same = first.log_type == second.log_type
+            && first.log_query.rw_flag == second.log_query.rw_flag
+            && first.log_query.read_value == second.log_query.read_value
+            && first.log_query.written_value == second.log_query.written_value;
+        if !is_same {
+            diffs.push((slot, StorageLogDiff::Mismatch { first, second }));
+        }
+    }
+    diffs.extend(
+        first
+            .into_iter()
+            .map(|(slot, first)| (slot, StorageLogDiff::OnlyInFirst(first))),
+    );
+    diffs.sort_by_key(|(slot, _)| *slot);
+    diffs.into_iter().map(|(_, diff)| diff).collect()
 }
 
 /// Receives sorted slice of timestamps.
@@ -235,32 +555,129 @@ pub fn precompile_calls_count_after_timestamp(
     sorted_timestamps.len() - sorted_timestamps.partition_point(|t| *t < from_timestamp)
 }
 
+/// Creates a fresh in-memory storage seeded with all system contracts for the specified chain.
+/// Use [`InMemoryStorage::with_custom_system_contracts_and_chain_id()`] to seed custom contracts instead.
+pub fn bootstrapped_storage(chain_id: L2ChainId) -> InMemoryStorage {
+    InMemoryStorage::with_system_contracts_and_chain_id(chain_id, hash_bytecode)
+}
+
 pub static BASE_SYSTEM_CONTRACTS: Lazy<BaseSystemContracts> =
     Lazy::new(BaseSystemContracts::load_from_disk);
 
+/// Returns the hash of the default account code from [`BASE_SYSTEM_CONTRACTS`], which is used in block properties.
+pub fn default_aa_code_hash() -> U256 {
+    h256_to_u256(BASE_SYSTEM_CONTRACTS.default_aa.hash)
+}
+
+/// Returns block properties with the specified zkPorter availability and [`default_aa_code_hash()`].
+/// The default account code hash can be overridden via the struct update syntax if necessary.
+pub fn block_properties(zkporter_is_available: bool) -> BlockProperties {
+    BlockProperties {
+        default_aa_code_hash: default_aa_code_hash(),
+        zkporter_is_available,
+    }
+}
+
+pub fn default_block_properties() -> BlockProperties {
+    block_properties(ZKPORTER_IS_AVAILABLE)
+}
+
 pub fn create_test_block_params() -> (BlockContext, BlockProperties) {
+    create_test_block_params_with(
+        1,
+        1000,
+        50_000_000_000, // 50 gwei
+        250_000_000,    // 0.25 gwei
+        H160::zero(),
+    )
+}
+
+/// Same as [`create_test_block_params()`], but with the specified block context values.
+///
+/// # Panics
+///
+/// Panics if the gas prices are not sane (see [`BlockContext::validate_gas_prices()`]).
+pub fn create_test_block_params_with(
+    block_number: u32,
+    block_timestamp: u64,
+    l1_gas_price: u64,
+    fair_l2_gas_price: u64,
+    operator_address: Address,
+) -> (BlockContext, BlockProperties) {
     let context = BlockContext {
-        block_number: 1u32,
-        block_timestamp: 1000,
-        l1_gas_price: 50_000_000_000,   // 50 gwei
-        fair_l2_gas_price: 250_000_000, // 0.25 gwei
-        operator_address: H160::zero(),
+        block_number,
+        block_timestamp,
+        l1_gas_price,
+        fair_l2_gas_price,
+        operator_address,
     };
+    context
+        .validate_gas_prices()
+        .expect("invalid gas prices in test block context");
 
-    (
-        context,
-        BlockProperties {
-            default_aa_code_hash: h256_to_u256(BASE_SYSTEM_CONTRACTS.default_aa.hash),
-            zkporter_is_available: ZKPORTER_IS_AVAILABLE,
-        },
-    )
+    (context, default_block_properties())
+}
+
+/// Returns the fee estimation bootloader together with a block context from [`create_test_block_params()`]
+/// using the specified L1 gas price, e.g. to calibrate fee estimation for different L1 gas prices.
+///
+/// # Panics
+///
+/// Panics if the L1 gas price is not sane (see [`BlockContext::validate_gas_prices()`]).
+pub fn fee_estimate_block(l1_gas_price: u64) -> (&'static SystemContractCode, BlockContext) {
+    let (context, _) = create_test_block_params();
+    let context = BlockContext {
+        l1_gas_price,
+        ..context
+    };
+    context
+        .validate_gas_prices()
+        .expect("invalid L1 gas price for fee estimation block");
+    (&*ESTIMATE_FEE_BLOCK_CODE, context)
 }
 
+const BOOTLOADER_TESTS_DIR: &str = "contracts/system-contracts/bootloader/tests/artifacts";
+
+/// Reads the code of the specified bootloader test, based on ZKSYNC_HOME environment variable.
+///
+/// # Panics
+///
+/// Panics if the test artifact cannot be read. See [`try_read_bootloader_test_code()`] for details.
 pub fn read_bootloader_test_code(test: &str) -> Vec<u8> {
-    read_zbin_bytecode(format!(
-        "contracts/system-contracts/bootloader/tests/artifacts/{}.yul/{}.yul.zbin",
-        test, test
-    ))
+    try_read_bootloader_test_code(test).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [`read_bootloader_test_code()`]. If the test artifact is missing (e.g., because
+/// bootloader tests were not rebuilt after adding the test), the returned error lists the available tests.
+pub fn try_read_bootloader_test_code(test: &str) -> io::Result<Vec<u8>> {
+    let tests_dir = zksync_home().join(BOOTLOADER_TESTS_DIR);
+    let artifact_path = tests_dir.join(format!("{test}.yul/{test}.yul.zbin"));
+    match fs::read(&artifact_path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let available_tests = available_bootloader_tests(&tests_dir);
+            let message = format!(
+                "bootloader test `{test}` is not compiled (no artifact at {artifact_path:?}); \
+                 available tests: [{}]",
+                available_tests.join(", ")
+            );
+            Err(io::Error::new(io::ErrorKind::NotFound, message))
+        }
+        result => result,
+    }
+}
+
+fn available_bootloader_tests(tests_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(tests_dir) else {
+        return vec![];
+    };
+    let mut tests: Vec<_> = entries
+        .filter_map(|entry| {
+            let dir_name = entry.ok()?.file_name().into_string().ok()?;
+            dir_name.strip_suffix(".yul").map(str::to_owned)
+        })
+        .collect();
+    tests.sort_unstable();
+    tests
 }
 
 pub(crate) fn calculate_computational_gas_used<
@@ -289,3 +706,250 @@ pub(crate) fn calculate_computational_gas_used<
             0
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_m6::{errors::VmRevertReason, history_recorder::HistoryDisabled};
+
+    fn fat_pointer(start: u32, offset: u32, length: u32) -> FatPointer {
+        FatPointer {
+            offset,
+            memory_page: INITIAL_BASE_PAGE,
+            start,
+            length,
+        }
+    }
+
+    fn memory_with_data() -> SimpleMemory<HistoryDisabled> {
+        let mut memory = SimpleMemory::default();
+        memory.populate_page(
+            INITIAL_BASE_PAGE as usize,
+            vec![(0, U256::from(0x0102_0304_u64))],
+            Timestamp(0),
+        );
+        memory
+    }
+
+    #[test]
+    fn creating_test_block_params() {
+        let operator_address = Address::repeat_byte(1);
+        let (context, properties) =
+            create_test_block_params_with(5, 2_000, 1_000_000_000, 100_000_000, operator_address);
+        assert_eq!(context.block_number, 5);
+        assert_eq!(context.block_timestamp, 2_000);
+        assert_eq!(context.l1_gas_price, 1_000_000_000);
+        assert_eq!(context.fair_l2_gas_price, 100_000_000);
+        assert_eq!(context.operator_address, operator_address);
+        assert_eq!(properties.default_aa_code_hash, default_aa_code_hash());
+        assert!(!properties.zkporter_is_available);
+    }
+
+    #[test]
+    fn converting_execution_result() {
+        let result = VmExecutionResult::Ok(vec![1, 2, 3]).into_result();
+        assert_eq!(result, Ok(vec![1, 2, 3]));
+
+        let err = VmExecutionResult::Revert(vec![]).into_result().unwrap_err();
+        assert_eq!(err, VmExecutionError::Revert(vec![]));
+        let revert_reason = err.revert_reason().unwrap().unwrap();
+        assert_eq!(
+            revert_reason,
+            VmRevertReason::Unknown {
+                function_selector: vec![],
+                data: vec![],
+            }
+        );
+
+        let err = VmExecutionResult::Panic.into_result().unwrap_err();
+        assert_eq!(err, VmExecutionError::Panic);
+        assert!(err.revert_reason().is_none());
+
+        let address = Address::repeat_byte(1);
+        let err = VmExecutionResult::MostLikelyDidNotFinish(address, 5)
+            .into_result()
+            .unwrap_err();
+        assert_eq!(err, VmExecutionError::DidNotFinish { address, pc: 5 });
+    }
+
+    #[test]
+    fn decoding_revert_reasons() {
+        let error_selector = [0x08, 0xc3, 0x79, 0xa0];
+        let data = ethabi::encode(&[ethabi::Token::String(
+            "ERC20: insufficient balance".to_owned(),
+        )]);
+        let data = [&error_selector[..], &data].concat();
+        assert_eq!(
+            decode_revert_reason(&data).as_deref(),
+            Some("ERC20: insufficient balance")
+        );
+
+        let panic_selector = [0x4e, 0x48, 0x7b, 0x71];
+        let data = ethabi::encode(&[ethabi::Token::Uint(0x11.into())]);
+        let data = [&panic_selector[..], &data].concat();
+        assert_eq!(
+            decode_revert_reason(&data).as_deref(),
+            Some("panic 0x11: arithmetic overflow or underflow")
+        );
+
+        assert_eq!(decode_revert_reason(&[]), None);
+        assert_eq!(decode_revert_reason(&[1, 2, 3, 4, 5]), None);
+        // Truncated `Error(string)` payload
+        assert_eq!(decode_revert_reason(&error_selector), None);
+    }
+
+    #[test]
+    fn dumping_memory_using_fat_pointer() {
+        let memory = memory_with_data();
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(0, 28, 32));
+        assert_eq!(dump, [1, 2, 3, 4]);
+        let dump = dump_memory_page_using_fat_pointer(&memory, fat_pointer(28, 2, 6));
+        assert_eq!(dump, [3, 4, 0, 0]);
+    }
+
+    #[test]
+    fn dumping_memory_using_typed_page() {
+        let memory = memory_with_data();
+        let page = MemoryPage(INITIAL_BASE_PAGE);
+        assert_eq!(dump_memory_page(&memory, page, 28, 4), [1, 2, 3, 4]);
+        assert_eq!(
+            try_dump_memory_page(&memory, page, 30, 4),
+            Ok(vec![3, 4, 0, 0])
+        );
+        let err = try_dump_memory_page(&memory, page, 1 << 24, 1).unwrap_err();
+        assert_eq!(err, MemoryDumpError::OffsetOutOfRange(1 << 24));
+    }
+
+    #[test]
+    fn dumping_memory_past_written_region() {
+        let memory = memory_with_data();
+        let page = MemoryPage(INITIAL_BASE_PAGE);
+        let dump = dump_memory_page(&memory, page, 0, 96);
+        let mut expected = vec![0_u8; 96];
+        expected[28..32].copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(dump, expected);
+
+        // Pages that were never written to are read as zeros as well.
+        let dump = dump_memory_page(&memory, MemoryPage(INITIAL_BASE_PAGE + 1), 16, 40);
+        assert_eq!(dump, [0; 40]);
+    }
+
+    #[test]
+    fn dumping_memory_with_cap() {
+        let memory = memory_with_data();
+        let dump = dump_memory_page_capped(&memory, INITIAL_BASE_PAGE, 28, 4, 8);
+        assert_eq!(dump, Ok((vec![1, 2, 3, 4], false)));
+        let dump = dump_memory_page_capped(&memory, INITIAL_BASE_PAGE, 28, 1 << 30, 2);
+        assert_eq!(dump, Ok((vec![1, 2], true)));
+
+        let err = dump_memory_page_capped(&memory, INITIAL_BASE_PAGE, 1 << 24, 4, 8).unwrap_err();
+        assert_eq!(err, MemoryDumpError::OffsetOutOfRange(1 << 24));
+    }
+
+    #[test]
+    fn dumping_memory_using_fat_pointer_with_offset_equal_to_length() {
+        let memory = memory_with_data();
+        let dump = try_dump_memory_page_using_fat_pointer(&memory, fat_pointer(0, 32, 32));
+        assert_eq!(dump, Ok(vec![]));
+    }
+
+    #[test]
+    fn dumping_memory_using_fat_pointer_with_offset_exceeding_length() {
+        let memory = memory_with_data();
+        let ptr = fat_pointer(0, 33, 32);
+        let err = try_dump_memory_page_using_fat_pointer(&memory, ptr).unwrap_err();
+        assert_eq!(
+            err,
+            MemoryDumpError::InvalidFatPointer {
+                start: 0,
+                offset: 33,
+                length: 32,
+            }
+        );
+        assert!(dump_memory_page_using_fat_pointer(&memory, ptr).is_empty());
+    }
+
+    #[test]
+    fn dumping_memory_using_fat_pointer_with_overflowing_start() {
+        let memory = memory_with_data();
+        let ptr = fat_pointer(u32::MAX, 1, 32);
+        let err = try_dump_memory_page_using_fat_pointer(&memory, ptr).unwrap_err();
+        assert_eq!(
+            err,
+            MemoryDumpError::InvalidFatPointer {
+                start: u32::MAX,
+                offset: 1,
+                length: 32,
+            }
+        );
+        assert!(dump_memory_page_using_fat_pointer(&memory, ptr).is_empty());
+
+        // The pointer doesn't overflow `u32`, but points outside the page.
+        let ptr = fat_pointer(u32::MAX - 1, 1, 32);
+        let err = try_dump_memory_page_using_fat_pointer(&memory, ptr).unwrap_err();
+        assert_eq!(err, MemoryDumpError::OffsetOutOfRange(u32::MAX as usize));
+        assert!(dump_memory_page_using_fat_pointer(&memory, ptr).is_empty());
+    }
+
+    #[test]
+    fn converting_byte_offsets_to_words() {
+        assert_eq!(byte_offset_to_word(0), (0, 0));
+        assert_eq!(byte_offset_to_word(33), (1, 1));
+        assert_eq!(word_to_byte_offset(1), 32);
+
+        let max_offset = word_to_byte_offset(u32::MAX) + 31;
+        assert_eq!(byte_offset_to_word(max_offset), (u32::MAX, 31));
+    }
+
+    #[test]
+    #[should_panic(expected = "word index doesn't fit into `u32`")]
+    fn converting_out_of_range_byte_offset_to_word() {
+        byte_offset_to_word(word_to_byte_offset(u32::MAX) + 32);
+    }
+
+    #[test]
+    fn dumping_memory_into_writer() {
+        let memory = memory_with_data();
+        let mut expected_unaligned = vec![4];
+        expected_unaligned.extend([0; 32]);
+        for (offset, length, expected_dump) in [
+            (0, 0, vec![]),
+            (28, 4, vec![1, 2, 3, 4]),
+            (30, 8, vec![3, 4, 0, 0, 0, 0, 0, 0]),
+            (31, 33, expected_unaligned),
+        ] {
+            let mut dump = Vec::<u8>::new();
+            dump_memory_page_into(&memory, INITIAL_BASE_PAGE, offset, length, &mut dump).unwrap();
+            assert_eq!(dump, expected_dump, "offset = {offset}, length = {length}");
+        }
+
+        let err = dump_memory_page_into(&memory, INITIAL_BASE_PAGE, 1 << 24, 1, &mut io::sink())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn iterating_over_h256_bytes() {
+        let hash = H256::from_low_u64_be(0x0102_0304);
+        let be_bytes: Vec<_> = hash.into_be_iter().collect();
+        assert_eq!(be_bytes, hash.as_bytes());
+        let le_bytes: Vec<_> = hash.into_le_iter().collect();
+        assert_eq!(le_bytes[..4], [4, 3, 2, 1]);
+
+        // Bytes must be consistent with the `U256` representation of the hash.
+        let value = U256::from_big_endian(hash.as_bytes());
+        assert!(value.into_be_iter().eq(be_bytes));
+        assert!(value.into_le_iter().eq(le_bytes));
+    }
+
+    #[test]
+    fn iterating_over_address_bytes() {
+        let address = Address::from_low_u64_be(0x0102_0304);
+        let be_bytes: Vec<_> = address.into_be_iter().collect();
+        assert_eq!(be_bytes, address.as_bytes());
+        let mut le_bytes: Vec<_> = address.into_le_iter().collect();
+        assert_eq!(le_bytes[..4], [4, 3, 2, 1]);
+        le_bytes.reverse();
+        assert_eq!(le_bytes, be_bytes);
+    }
+}
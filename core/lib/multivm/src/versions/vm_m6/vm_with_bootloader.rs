@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
 use zk_evm_1_3_1::{
     abstractions::{MAX_HEAP_PAGE_SIZE_IN_WORDS, MAX_MEMORY_BYTES},
@@ -25,6 +25,7 @@ use zksync_utils::{
 
 use crate::vm_m6::{
     bootloader_state::BootloaderState,
+    errors::GasPriceError,
     history_recorder::HistoryMode,
     storage::Storage,
     transaction_data::{TransactionData, L1_TX_TYPE},
@@ -57,9 +58,43 @@ pub struct BlockContext {
 }
 
 impl BlockContext {
+    /// Default range of sane gas prices used by [`Self::validate_gas_prices()`]: from 1 wei to 10,000 gwei.
+    pub const SANE_GAS_PRICE_RANGE: RangeInclusive<u64> = 1..=10_000_000_000_000;
+
     pub fn block_gas_price_per_pubdata(&self) -> u64 {
         derive_base_fee_and_gas_per_pubdata(self.l1_gas_price, self.fair_l2_gas_price).1
     }
+
+    /// Checks that gas prices in this context are non-zero and lie within [`Self::SANE_GAS_PRICE_RANGE`].
+    pub fn validate_gas_prices(&self) -> Result<(), GasPriceError> {
+        self.validate_gas_prices_within(Self::SANE_GAS_PRICE_RANGE)
+    }
+
+    /// Checks that gas prices in this context are non-zero and lie within the specified range.
+    pub fn validate_gas_prices_within(
+        &self,
+        sane_range: RangeInclusive<u64>,
+    ) -> Result<(), GasPriceError> {
+        if self.l1_gas_price == 0 {
+            return Err(GasPriceError::ZeroL1GasPrice);
+        }
+        if self.fair_l2_gas_price == 0 {
+            return Err(GasPriceError::ZeroFairL2GasPrice);
+        }
+        if !sane_range.contains(&self.l1_gas_price) {
+            return Err(GasPriceError::L1GasPriceOutOfRange {
+                price: self.l1_gas_price,
+                range: sane_range,
+            });
+        }
+        if !sane_range.contains(&self.fair_l2_gas_price) {
+            return Err(GasPriceError::FairL2GasPriceOutOfRange {
+                price: self.fair_l2_gas_price,
+                range: sane_range,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// Besides the raw values from the `BlockContext`, contains the values that are to be derived
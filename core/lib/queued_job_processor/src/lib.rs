@@ -1,15 +1,37 @@
 use std::{
-    fmt::Debug,
-    time::{Duration, Instant},
+    collections::HashMap,
+    error,
+    fmt::{self, Debug},
+    future::{self, Future},
+    hash::Hash,
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::Context as _;
 pub use async_trait::async_trait;
-use tokio::{sync::watch, task::JoinHandle, time::sleep};
-use vise::{Buckets, Counter, Histogram, LabeledFamily, Metrics};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+    time::{sleep, timeout},
+};
+pub use tracing::Level as LogLevel;
+use tracing::{Instrument, Span};
+use vise::{Buckets, Counter, EncodeLabelValue, Histogram, LabeledFamily, Metrics, Unit};
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
 const ATTEMPT_BUCKETS: Buckets = Buckets::exponential(1.0..=64.0, 2.0);
+/// Job durations in seconds; jobs may take from about a second to more than an hour.
+const JOB_DURATION_BUCKETS: Buckets = Buckets::exponential(1.0..=4_096.0, 2.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum JobStage {
+    Started,
+    Succeeded,
+    Failed,
+    Requeued,
+}
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "job_processor")]
@@ -18,20 +40,201 @@ struct JobProcessorMetrics {
     max_attempts_reached: LabeledFamily<(&'static str, String), Counter, 2>,
     #[metrics(labels = ["service_name"], buckets = ATTEMPT_BUCKETS)]
     attempts: LabeledFamily<&'static str, Histogram<usize>>,
+    #[metrics(labels = ["service_name"], buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    connection_acquisition_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of jobs that were started, succeeded, failed or requeued.
+    #[metrics(labels = ["service_name", "stage"])]
+    jobs: LabeledFamily<(&'static str, JobStage), Counter, 2>,
+    /// Duration of processing finished jobs, measured from the moment the job was returned by `get_next_job`.
+    #[metrics(labels = ["service_name"], buckets = JOB_DURATION_BUCKETS, unit = Unit::Seconds)]
+    duration: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Time that jobs have spent in the queue before being returned by `get_next_job`.
+    #[metrics(labels = ["service_name"], buckets = JOB_DURATION_BUCKETS, unit = Unit::Seconds)]
+    queue_latency: LabeledFamily<&'static str, Histogram<Duration>>,
 }
 
 #[vise::register]
 static METRICS: vise::Global<JobProcessorMetrics> = vise::Global::new();
 
+/// Error that can be returned by a task spawned in [`JobProcessor::process_job()`] to put the job back
+/// into the queue (e.g., if the job is meant for another shard), rather than marking it as failed.
+/// Such jobs are routed to [`JobProcessor::requeue()`].
+#[derive(Debug)]
+pub struct RequeueJob {
+    pub reason: String,
+}
+
+impl fmt::Display for RequeueJob {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "job requeue requested: {}", self.reason)
+    }
+}
+
+impl error::Error for RequeueJob {}
+
+/// Error that can be returned by a task spawned in [`JobProcessor::process_job()`] to specify how the failure
+/// should be handled. Other errors and panics are treated as permanent failures, but, unlike [`Self::Permanent`],
+/// they are retried up to [`JobProcessor::MAX_ATTEMPTS`] times.
+#[derive(Debug)]
+pub enum JobFailure {
+    /// Failure caused by a temporary issue (e.g., a DB deadlock or a network blip). The job is routed
+    /// to [`JobProcessor::requeue()`] so that it's processed again, unless the attempt limit is reached
+    /// (i.e., [`JobProcessor::max_attempts()`], or [`JobProcessor::MAX_ATTEMPTS`] if retries are enabled),
+    /// in which case the job is routed to [`JobProcessor::save_failure()`].
+    Transient(String),
+    /// Failure that won't go away if the job is retried (e.g., the job is malformed). The job is routed
+    /// to [`JobProcessor::save_permanent_failure()`] without retries.
+    Permanent(String),
+}
+
+impl fmt::Display for JobFailure {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transient(reason) => write!(formatter, "transient job failure: {reason}"),
+            Self::Permanent(reason) => write!(formatter, "permanent job failure: {reason}"),
+        }
+    }
+}
+
+impl error::Error for JobFailure {}
+
+/// Outcome of a job processed by a [`JobProcessor`], published to [`JobProcessor::outcome_sink()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobOutcome<Id> {
+    /// The job was successfully processed, and its result was saved.
+    Success { job_id: Id },
+    /// The job has failed, and the failure was saved.
+    Failure { job_id: Id, error: String },
+}
+
+/// Priority of a job, used by [`JobProcessor::get_next_job_with_priority()`]. Priorities are ordered
+/// from the lowest to the highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JobPriority {
+    Low,
+    Normal,
+    High,
+}
+
+/// Futures of in-flight jobs in [`JobProcessor::run()`], each resolving to the job ID, the attempt number
+/// and the result of [`JobProcessor::wait_for_task()`].
+type InFlightJobs<'a, Id> = FuturesUnordered<BoxFuture<'a, (Id, u32, anyhow::Result<bool>)>>;
+
+/// Jobs tracked by [`JobProcessor::run()`] to retry failed jobs.
+#[derive(Debug)]
+struct JobTracker<Id> {
+    attempts: HashMap<Id, u32>,
+    consecutive_failures: u32,
+}
+
+impl<Id: Eq + Hash> JobTracker<Id> {
+    fn new() -> Self {
+        Self {
+            attempts: HashMap::new(),
+            consecutive_failures: 0,
+        }
+    }
+
+    /// Returns the 1-based number of the attempt to process a job that is being started.
+    fn next_attempt(&self, job_id: &Id) -> u32 {
+        self.attempts.get(job_id).copied().unwrap_or(0) + 1
+    }
+
+    fn record(&mut self, job_id: Id, attempt: u32, failed: bool, max_attempts: u32) {
+        if failed {
+            self.consecutive_failures += 1;
+        } else {
+            self.consecutive_failures = 0;
+        }
+        if failed && attempt < max_attempts {
+            self.attempts.insert(job_id, attempt);
+        } else {
+            // The job will not be retried.
+            self.attempts.remove(&job_id);
+        }
+    }
+
+    /// Awaits `future` while concurrently driving `in_flight` jobs and recording their outcomes, so that
+    /// the outcomes are saved promptly even if `future` takes a while (e.g., polling for new jobs or backing off).
+    async fn drive<F: Future>(
+        &mut self,
+        in_flight: &mut InFlightJobs<'_, Id>,
+        max_attempts: u32,
+        future: F,
+    ) -> anyhow::Result<F::Output> {
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                output = &mut future => return Ok(output),
+                // If there are no in-flight jobs, the branch is disabled.
+                Some((job_id, attempt, result)) = in_flight.next() => {
+                    let failed = result.context("wait_for_task")?;
+                    self.record(job_id, attempt, failed, max_attempts);
+                }
+            }
+        }
+    }
+
+    /// Awaits all `in_flight` jobs and records their outcomes. Unlike propagating the first error immediately,
+    /// this doesn't leave the remaining jobs unaccounted for; the first error is stored in `first_error` instead.
+    /// Cancel-safe: if the returned future is dropped, the remaining jobs stay in `in_flight`.
+    async fn drain(
+        &mut self,
+        in_flight: &mut InFlightJobs<'_, Id>,
+        max_attempts: u32,
+        first_error: &mut Option<anyhow::Error>,
+    ) {
+        while let Some((job_id, attempt, result)) = in_flight.next().await {
+            let failed = result.context("wait_for_task").unwrap_or_else(|err| {
+                first_error.get_or_insert(err);
+                true
+            });
+            self.record(job_id, attempt, failed, max_attempts);
+        }
+    }
+}
+
 #[async_trait]
 pub trait JobProcessor: Sync + Send {
     type Job: Send + 'static;
-    type JobId: Send + Sync + Debug + 'static;
+    type JobId: Send + Sync + Debug + Clone + Eq + Hash + 'static;
     type JobArtifacts: Send + 'static;
 
     const POLLING_INTERVAL_MS: u64 = 1000;
+    /// Initial interval of polling for new jobs by [`Self::run()`] if `get_next_job` returns `None`. The interval grows
+    /// exponentially (see [`Self::BACKOFF_MULTIPLIER`]) up to [`Self::MAX_BACKOFF_MS`] while there are no jobs, and is reset
+    /// to this value once a job is found. To poll with a constant interval, set both bounds to the same value.
+    const MIN_BACKOFF_MS: u64 = Self::POLLING_INTERVAL_MS;
     const MAX_BACKOFF_MS: u64 = 60_000;
     const BACKOFF_MULTIPLIER: u64 = 2;
+    /// Maximum number of jobs processed by [`Self::run()`] concurrently.
+    const MAX_CONCURRENT_JOBS: usize = 1;
+    /// Maximum number of attempts to process a job within [`Self::run()`]. If a job fails and attempts remain,
+    /// it is handed to [`Self::mark_for_retry()`] instead of [`Self::save_failure()`]. By default, jobs are not retried.
+    ///
+    /// Unlike [`Self::max_attempts()`], which is only used for reporting, this limit is enforced by the framework.
+    const MAX_ATTEMPTS: u32 = 1;
+    /// Maximum duration of processing a job, measured from the moment it was returned by `get_next_job`.
+    /// If a job takes longer, its task is aborted, and the job is considered failed. By default, jobs are not timed out.
+    ///
+    /// Aborting only takes effect once the task reaches an `.await` point (e.g., a DB query), so the job
+    /// should be processed asynchronously for the timeout to stop it; blocking tasks cannot be aborted and run to completion
+    /// in the background (although their results are discarded).
+    const JOB_TIMEOUT: Option<Duration> = None;
+    /// Time given to in-flight jobs to finish after the stop signal is received. Once it elapses, tasks
+    /// of the remaining jobs are aborted, and the jobs are handled as failed (i.e., routed to [`Self::save_failure()`]
+    /// or [`Self::mark_for_retry()`]). Jobs whose tasks have already finished are not affected, so that their
+    /// outcomes are saved as usual.
+    const SHUTDOWN_GRACE: Duration = Duration::from_secs(60);
+    /// Whether to report generic job metrics (started / succeeded / failed / requeued jobs and job duration) labeled
+    /// by [`Self::SERVICE_NAME`]; see [`Self::on_job_started()`], [`Self::on_job_finished()`]
+    /// and [`Self::on_job_requeued()`].
+    const EMIT_METRICS: bool = true;
+    /// Minimum priority of jobs taken by [`Self::run()`]; see [`Self::get_next_job_with_priority()`].
+    const MIN_JOB_PRIORITY: JobPriority = JobPriority::Low;
+    /// If set, [`Self::run()`] logs the number of pending jobs (see [`Self::pending_jobs_count()`]) at most once
+    /// per this interval. By default, the number of pending jobs is not logged.
+    const PENDING_JOBS_LOG_INTERVAL: Option<Duration> = None;
     const SERVICE_NAME: &'static str;
 
     /// Returns None when there is no pending job
@@ -39,11 +242,46 @@ pub trait JobProcessor: Sync + Send {
     /// Note: must be concurrency-safe - that is, one job must not be returned in two parallel processes
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>>;
 
+    /// Same as [`Self::get_next_job()`], but only returns jobs with priority not lower than `min_priority`
+    /// (preferring jobs with higher priority). Used by `run` with [`Self::MIN_JOB_PRIORITY`].
+    /// The same concurrency-safety requirement applies: one job must not be returned in two parallel processes.
+    ///
+    /// The default implementation ignores priorities and delegates to `get_next_job`.
+    async fn get_next_job_with_priority(
+        &self,
+        min_priority: JobPriority,
+    ) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
+        let _ = min_priority;
+        self.get_next_job().await
+    }
+
+    /// Returns the number of jobs waiting to be processed, e.g. to autoscale job processors.
+    /// Should be cheap, since it may be called periodically by `run` (see [`Self::PENDING_JOBS_LOG_INTERVAL`]).
+    ///
+    /// The default implementation always returns 0.
+    async fn pending_jobs_count(&self) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+
     /// Invoked when `process_job` panics
     /// Should mark the job as failed
     async fn save_failure(&self, job_id: Self::JobId, started_at: Instant, error: String);
 
-    /// Function that processes a job
+    /// Invoked when the task spawned by `process_job` returns a [`JobFailure::Permanent`] error.
+    /// Should mark the job as failed so that it's not picked up again (e.g., move it to a dead-letter queue).
+    ///
+    /// The default implementation delegates to [`Self::save_failure()`].
+    async fn save_permanent_failure(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        error: String,
+    ) {
+        self.save_failure(job_id, started_at, error).await;
+    }
+
+    /// Function that processes a job. The spawned task may return a [`RequeueJob`] or [`JobFailure`] error
+    /// to control how the job is handled by `wait_for_task`.
     async fn process_job(
         &self,
         job: Self::Job,
@@ -54,6 +292,15 @@ pub trait JobProcessor: Sync + Send {
     /// To run indefinitely, pass `None`,
     /// To process one job, pass `Some(1)`,
     /// To process a batch, pass `Some(batch_size)`.
+    ///
+    /// Up to [`Self::MAX_CONCURRENT_JOBS`] jobs are processed concurrently; each job is independently
+    /// awaited in `wait_for_task`, which saves its outcome. In-flight jobs are driven while `run` polls for new jobs
+    /// or backs off, so their outcomes are saved as soon as they're ready.
+    ///
+    /// Once the stop signal is received, no new jobs are taken, and in-flight jobs are given [`Self::SHUTDOWN_GRACE`]
+    /// to finish; their outcomes are routed to `save_result` / `save_failure` as usual. Jobs that don't finish in time
+    /// are aborted and handled as failed. Saving job outcomes is never interrupted, so `run` never leaves unaccounted
+    /// jobs behind on shutdown.
     async fn run(
         self,
         stop_receiver: watch::Receiver<bool>,
@@ -62,100 +309,519 @@ pub trait JobProcessor: Sync + Send {
     where
         Self: Sized,
     {
-        let mut backoff: u64 = Self::POLLING_INTERVAL_MS;
-        while iterations_left.map_or(true, |i| i > 0) {
+        let mut backoff: u64 = Self::MIN_BACKOFF_MS;
+        let mut is_busy = false;
+        let mut pending_jobs_logged_at: Option<Instant> = None;
+        let mut jobs = JobTracker::new();
+        let mut in_flight: InFlightJobs<'_, Self::JobId> = FuturesUnordered::new();
+        // Signals in-flight jobs to abort their tasks once the shutdown grace period has elapsed.
+        let (interrupt_sender, interrupt_receiver) = watch::channel(false);
+        let this = &self;
+        loop {
             if *stop_receiver.borrow() {
-                tracing::warn!(
-                    "Stop signal received, shutting down {} component while waiting for a new job",
-                    Self::SERVICE_NAME
+                self.log_event(
+                    LogLevel::WARN,
+                    &format!(
+                        "Stop signal received, shutting down {} component with {} job(s) in flight",
+                        Self::SERVICE_NAME,
+                        in_flight.len()
+                    ),
                 );
-                return Ok(());
+                let mut first_error = None;
+                let drain = jobs.drain(&mut in_flight, Self::MAX_ATTEMPTS, &mut first_error);
+                if timeout(Self::SHUTDOWN_GRACE, drain).await.is_err() {
+                    self.log_event(
+                        LogLevel::WARN,
+                        &format!(
+                            "Shutdown grace period has elapsed; interrupting {} {} job(s)",
+                            in_flight.len(),
+                            Self::SERVICE_NAME
+                        ),
+                    );
+                    // Jobs whose tasks have already finished are still saving their outcomes; this must not be
+                    // interrupted. Other jobs abort their tasks and are handled as failed.
+                    interrupt_sender.send_replace(true);
+                    jobs.drain(&mut in_flight, Self::MAX_ATTEMPTS, &mut first_error)
+                        .await;
+                }
+                return first_error.map_or(Ok(()), Err);
             }
-            if let Some((job_id, job)) =
-                Self::get_next_job(&self).await.context("get_next_job()")?
-            {
+
+            let can_take_job = in_flight.len() < Self::MAX_CONCURRENT_JOBS
+                && iterations_left.map_or(true, |i| i > 0);
+            if !can_take_job {
+                if in_flight.is_empty() {
+                    // The requested number of jobs is processed.
+                    break;
+                }
+                // Wait for an in-flight job to finish, periodically checking the stop signal.
+                let poll_interval = Duration::from_millis(Self::POLLING_INTERVAL_MS);
+                if let Ok(Some((job_id, attempt, result))) =
+                    timeout(poll_interval, in_flight.next()).await
+                {
+                    let failed = result.context("wait_for_task")?;
+                    jobs.record(job_id, attempt, failed, Self::MAX_ATTEMPTS);
+                }
+                continue;
+            }
+            if Self::MAX_ATTEMPTS > 1 && jobs.consecutive_failures > 0 {
+                // Back off after consecutive failures, so that failing jobs aren't retried in a tight loop.
+                let exponent = jobs.consecutive_failures.min(16) - 1;
+                let failure_backoff = Self::POLLING_INTERVAL_MS
+                    .saturating_mul(Self::BACKOFF_MULTIPLIER.saturating_pow(exponent))
+                    .min(Self::MAX_BACKOFF_MS);
+                self.log_event(
+                    LogLevel::DEBUG,
+                    &format!(
+                        "Backing off for {failure_backoff} ms after {} consecutive failed job(s)",
+                        jobs.consecutive_failures
+                    ),
+                );
+                let failure_backoff = sleep(Duration::from_millis(failure_backoff));
+                jobs.drive(&mut in_flight, Self::MAX_ATTEMPTS, failure_backoff)
+                    .await?;
+            }
+            if let Some(log_interval) = Self::PENDING_JOBS_LOG_INTERVAL {
+                if pending_jobs_logged_at.map_or(true, |at| at.elapsed() >= log_interval) {
+                    pending_jobs_logged_at = Some(Instant::now());
+                    let pending_jobs_count = self.pending_jobs_count();
+                    match jobs
+                        .drive(&mut in_flight, Self::MAX_ATTEMPTS, pending_jobs_count)
+                        .await?
+                    {
+                        Ok(count) => self.log_event(
+                            LogLevel::INFO,
+                            &format!("{count} {} job(s) pending", Self::SERVICE_NAME),
+                        ),
+                        Err(err) => self.log_event(
+                            LogLevel::WARN,
+                            &format!("Failed getting the number of pending jobs: {err:#}"),
+                        ),
+                    }
+                }
+            }
+            let next_job = self.get_next_job_with_priority(Self::MIN_JOB_PRIORITY);
+            let next_job = jobs
+                .drive(&mut in_flight, Self::MAX_ATTEMPTS, next_job)
+                .await?
+                .context("get_next_job_with_priority()")?;
+            if let Some((job_id, job)) = next_job {
+                if !is_busy {
+                    is_busy = true;
+                    self.on_busy();
+                }
                 let started_at = Instant::now();
-                backoff = Self::POLLING_INTERVAL_MS;
+                if let Some(enqueued_at) = self.job_enqueued_at(&job) {
+                    self.observe_queue_latency(&job_id, enqueued_at, SystemTime::now());
+                }
+                self.on_job_started();
+                backoff = Self::MIN_BACKOFF_MS;
                 iterations_left = iterations_left.map(|i| i - 1);
 
-                tracing::debug!(
-                    "Spawning thread processing {:?} job with id {:?}",
-                    Self::SERVICE_NAME,
-                    job_id
+                self.log_event(
+                    LogLevel::DEBUG,
+                    &format!(
+                        "Spawning thread processing {:?} job with id {:?}",
+                        Self::SERVICE_NAME,
+                        job_id
+                    ),
+                );
+                // The span covers the entire job lifecycle; its `outcome` is recorded in `wait_for_task`.
+                let span = tracing::info_span!(
+                    "job",
+                    service = Self::SERVICE_NAME,
+                    job_id = ?job_id,
+                    outcome = tracing::field::Empty
+                );
+                let task = self.process_job(job, started_at).instrument(span.clone());
+                let task = jobs.drive(&mut in_flight, Self::MAX_ATTEMPTS, task).await?;
+                let attempt = jobs.next_attempt(&job_id);
+                let interrupted = interrupt_receiver.clone();
+                in_flight.push(
+                    async move {
+                        let result = this
+                            .wait_for_task(job_id.clone(), started_at, attempt, task, interrupted)
+                            .await;
+                        (job_id, attempt, result)
+                    }
+                    .instrument(span)
+                    .boxed(),
                 );
-                let task = self.process_job(job, started_at).await;
-
-                self.wait_for_task(job_id, started_at, task)
-                    .await
-                    .context("wait_for_task")?;
             } else if iterations_left.is_some() {
-                tracing::info!("No more jobs to process. Server can stop now.");
+                while let Some((job_id, attempt, result)) = in_flight.next().await {
+                    let failed = result.context("wait_for_task")?;
+                    jobs.record(job_id, attempt, failed, Self::MAX_ATTEMPTS);
+                }
+                if is_busy {
+                    self.on_idle();
+                }
+                self.log_event(
+                    LogLevel::INFO,
+                    "No more jobs to process. Server can stop now.",
+                );
                 return Ok(());
+            } else if !in_flight.is_empty() {
+                // Wait for an in-flight job to finish, but poll for new jobs after the backoff.
+                let backoff_duration = Duration::from_millis(backoff);
+                if let Ok(Some((job_id, attempt, result))) =
+                    timeout(backoff_duration, in_flight.next()).await
+                {
+                    let failed = result.context("wait_for_task")?;
+                    jobs.record(job_id, attempt, failed, Self::MAX_ATTEMPTS);
+                }
+                backoff = Self::next_backoff(backoff);
             } else {
-                tracing::trace!("Backing off for {} ms", backoff);
+                if is_busy {
+                    is_busy = false;
+                    self.on_idle();
+                }
+                self.log_event(LogLevel::TRACE, &format!("Backing off for {} ms", backoff));
                 sleep(Duration::from_millis(backoff)).await;
-                backoff = (backoff * Self::BACKOFF_MULTIPLIER).min(Self::MAX_BACKOFF_MS);
+                backoff = Self::next_backoff(backoff);
             }
         }
-        tracing::info!("Requested number of jobs is processed. Server can stop now.");
+        self.log_event(
+            LogLevel::INFO,
+            "Requested number of jobs is processed. Server can stop now.",
+        );
         Ok(())
     }
 
-    /// Polls task handle, saving its outcome.
+    /// Computes the next interval of polling for new jobs in [`Self::run()`], or the next restart delay
+    /// in [`Self::run_supervised()`].
+    fn next_backoff(backoff: u64) -> u64 {
+        backoff.saturating_mul(Self::BACKOFF_MULTIPLIER).clamp(
+            Self::MIN_BACKOFF_MS,
+            Self::MAX_BACKOFF_MS.max(Self::MIN_BACKOFF_MS),
+        )
+    }
+
+    /// Takes a single job via `get_next_job` (respecting [`Self::MIN_JOB_PRIORITY`]), processes it and returns
+    /// the job artifacts (or the error / panic message). Unlike `run`, the outcome is *not* saved (i.e., neither
+    /// `save_result` nor `save_failure` is called), and [`Self::JOB_TIMEOUT`] is not enforced. Useful for tests
+    /// and one-off tooling.
+    ///
+    /// Returns `None` if there are no pending jobs.
+    async fn process_single_job(
+        &self,
+    ) -> anyhow::Result<Option<(Self::JobId, Result<Self::JobArtifacts, String>)>> {
+        let next_job = self
+            .get_next_job_with_priority(Self::MIN_JOB_PRIORITY)
+            .await
+            .context("get_next_job_with_priority()")?;
+        let Some((job_id, job)) = next_job else {
+            return Ok(None);
+        };
+        let started_at = Instant::now();
+        let task = self.process_job(job, started_at).await;
+        let result = match task.await {
+            Ok(Ok(artifacts)) => Ok(artifacts),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(error) => Err(try_extract_panic_message(error)),
+        };
+        Ok(Some((job_id, result)))
+    }
+
+    /// Same as [`Self::run()`], but restarts the processing loop if it panics, up to `max_restarts` times
+    /// with exponential backoff (see [`Self::next_backoff()`]). Panics in tasks spawned by `process_job`
+    /// are handled by `run` itself and do not lead to restarts.
+    ///
+    /// Since `run` consumes the processor, each restart runs on a fresh clone of `self`; thus, the processor
+    /// must be cheaply cloneable (e.g., hold a connection pool and `Arc`s). Each restart starts
+    /// with the original `iterations_left`.
+    async fn run_supervised(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+        iterations_left: Option<usize>,
+        max_restarts: u32,
+    ) -> anyhow::Result<()>
+    where
+        Self: Sized + Clone + 'static,
+    {
+        let mut restarts = 0;
+        let mut backoff = Self::MIN_BACKOFF_MS;
+        loop {
+            let processor = self.clone();
+            let task = tokio::spawn(processor.run(stop_receiver.clone(), iterations_left));
+            let panic = match task.await {
+                Ok(result) => return result,
+                Err(err) if err.is_panic() => try_extract_panic_message(err),
+                Err(err) => return Err(err).context("run() task was cancelled"),
+            };
+
+            if restarts >= max_restarts {
+                anyhow::bail!(
+                    "{} processing loop panicked after {restarts} restart(s): {panic}",
+                    Self::SERVICE_NAME
+                );
+            }
+            if *stop_receiver.borrow() {
+                return Ok(());
+            }
+            restarts += 1;
+            self.log_event(
+                LogLevel::ERROR,
+                &format!(
+                    "{} processing loop panicked: {panic}; restarting in {backoff} ms \
+                     (restart {restarts} of {max_restarts})",
+                    Self::SERVICE_NAME
+                ),
+            );
+            sleep(Duration::from_millis(backoff)).await;
+            backoff = Self::next_backoff(backoff);
+        }
+    }
+
+    /// Polls task handle, saving its outcome. `attempt` is the 1-based number of the attempt to process the job
+    /// within `run`; if the job fails before [`Self::MAX_ATTEMPTS`] is reached, it's handed to [`Self::mark_for_retry()`].
+    ///
+    /// If `interrupted` is set before the task finishes, the task is aborted, and the job is considered failed.
+    /// Once the task has finished, its outcome is saved regardless of `interrupted`.
+    ///
+    /// Returns `true` if the job has failed (regardless of whether it will be retried).
+    ///
+    /// The job outcome (`succeeded`, `failed`, `retried` or `requeued`) is recorded in the `outcome` field
+    /// of the current tracing span, if it has one (`run` executes each job in a `job` span).
     async fn wait_for_task(
         &self,
         job_id: Self::JobId,
         started_at: Instant,
-        task: JoinHandle<anyhow::Result<Self::JobArtifacts>>,
-    ) -> anyhow::Result<()> {
+        attempt: u32,
+        mut task: JoinHandle<anyhow::Result<Self::JobArtifacts>>,
+        mut interrupted: watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
         let attempts = self.get_job_attempts(&job_id).await?;
         let max_attempts = self.max_attempts();
         if attempts == max_attempts {
             METRICS.max_attempts_reached[&(Self::SERVICE_NAME, format!("{job_id:?}"))].inc();
-            tracing::error!(
-                "Max attempts ({max_attempts}) reached for {} job {:?}",
-                Self::SERVICE_NAME,
-                job_id,
+            self.log_event(
+                LogLevel::ERROR,
+                &format!(
+                    "Max attempts ({max_attempts}) reached for {} job {:?}",
+                    Self::SERVICE_NAME,
+                    job_id,
+                ),
             );
         }
 
-        let result = loop {
-            tracing::trace!(
-                "Polling {} task with id {:?}. Is finished: {}",
+        let poll_task = async {
+            loop {
+                self.log_event(
+                    LogLevel::TRACE,
+                    &format!(
+                        "Polling {} task with id {:?}. Is finished: {}",
+                        Self::SERVICE_NAME,
+                        job_id,
+                        task.is_finished()
+                    ),
+                );
+                if task.is_finished() {
+                    break (&mut task).await;
+                }
+                sleep(Duration::from_millis(Self::POLLING_INTERVAL_MS)).await;
+            }
+        };
+        let job_timeout = async {
+            match Self::JOB_TIMEOUT {
+                Some(job_timeout) => sleep(job_timeout.saturating_sub(started_at.elapsed())).await,
+                None => future::pending().await,
+            }
+        };
+        let result = tokio::select! {
+            biased;
+            result = poll_task => Ok(result),
+            () = job_timeout => Err(format!(
+                "{} job {:?} timed out after {} seconds",
                 Self::SERVICE_NAME,
                 job_id,
-                task.is_finished()
-            );
-            if task.is_finished() {
-                break task.await;
+                Self::JOB_TIMEOUT.unwrap_or_default().as_secs_f64()
+            )),
+            // If the sender is dropped, the branch is disabled, i.e., the job is never interrupted.
+            Ok(_) = interrupted.wait_for(|&interrupted| interrupted) => Err(format!(
+                "{} job {:?} was interrupted by shutdown",
+                Self::SERVICE_NAME,
+                job_id
+            )),
+        };
+        let result = match result {
+            // The task may have finished after it was last polled; its outcome must not be discarded in this case.
+            Err(_) if task.is_finished() => Ok((&mut task).await),
+            Err(error_message) => {
+                task.abort();
+                Err(error_message)
             }
-            sleep(Duration::from_millis(Self::POLLING_INTERVAL_MS)).await;
+            Ok(result) => Ok(result),
         };
+
         let error_message = match result {
-            Ok(Ok(data)) => {
-                tracing::debug!(
-                    "{} Job {:?} finished successfully",
-                    Self::SERVICE_NAME,
-                    job_id
+            Err(error_message) => error_message,
+            Ok(Ok(Ok(data))) => {
+                self.log_event(
+                    LogLevel::DEBUG,
+                    &format!(
+                        "{} Job {:?} finished successfully",
+                        Self::SERVICE_NAME,
+                        job_id
+                    ),
                 );
                 METRICS.attempts[&Self::SERVICE_NAME].observe(attempts as usize);
-                return self
-                    .save_result(job_id, started_at, data)
+                self.save_result(job_id.clone(), started_at, data)
                     .await
-                    .context("save_result()");
+                    .context("save_result()")?;
+                self.on_job_finished(started_at, true);
+                self.publish_outcome(JobOutcome::Success { job_id });
+                Span::current().record("outcome", "succeeded");
+                return Ok(false);
             }
-            Ok(Err(error)) => error.to_string(),
-            Err(error) => try_extract_panic_message(error),
+            Ok(Ok(Err(error))) => {
+                let error = match error.downcast::<RequeueJob>() {
+                    Ok(RequeueJob { reason }) => {
+                        self.log_event(
+                            LogLevel::INFO,
+                            &format!(
+                                "{} job {:?} is requeued: {reason}",
+                                Self::SERVICE_NAME,
+                                job_id
+                            ),
+                        );
+                        self.on_job_requeued(started_at);
+                        self.requeue(job_id, started_at, reason)
+                            .await
+                            .context("requeue()")?;
+                        Span::current().record("outcome", "requeued");
+                        return Ok(false);
+                    }
+                    Err(error) => error,
+                };
+                match error.downcast::<JobFailure>() {
+                    Ok(JobFailure::Transient(reason)) => {
+                        // If retries are disabled (which is the default), `MAX_ATTEMPTS` is not applied; otherwise,
+                        // transient failures would never be requeued.
+                        let attempts_exhausted = attempts >= max_attempts
+                            || (Self::MAX_ATTEMPTS > 1 && attempt >= Self::MAX_ATTEMPTS);
+                        if attempts_exhausted {
+                            let error = format!("transient failure: {reason}");
+                            self.log_event(
+                                LogLevel::ERROR,
+                                &format!(
+                                    "{} job {:?} failed transiently, and no attempts are left: {reason}",
+                                    Self::SERVICE_NAME,
+                                    job_id
+                                ),
+                            );
+                            self.on_job_finished(started_at, false);
+                            self.save_failure(job_id.clone(), started_at, error.clone())
+                                .await;
+                            self.publish_outcome(JobOutcome::Failure { job_id, error });
+                            Span::current().record("outcome", "failed");
+                            return Ok(true);
+                        }
+
+                        self.log_event(
+                            LogLevel::WARN,
+                            &format!(
+                                "{} job {:?} failed transiently and is requeued: {reason}",
+                                Self::SERVICE_NAME,
+                                job_id
+                            ),
+                        );
+                        self.on_job_requeued(started_at);
+                        let reason = format!("transient failure: {reason}");
+                        self.requeue(job_id, started_at, reason)
+                            .await
+                            .context("requeue()")?;
+                        Span::current().record("outcome", "requeued");
+                        return Ok(true);
+                    }
+                    Ok(JobFailure::Permanent(reason)) => {
+                        self.log_event(
+                            LogLevel::ERROR,
+                            &format!(
+                                "{} job {:?} failed permanently: {reason}",
+                                Self::SERVICE_NAME,
+                                job_id
+                            ),
+                        );
+                        self.on_job_finished(started_at, false);
+                        self.save_permanent_failure(job_id.clone(), started_at, reason.clone())
+                            .await;
+                        self.publish_outcome(JobOutcome::Failure {
+                            job_id,
+                            error: reason,
+                        });
+                        Span::current().record("outcome", "failed");
+                        return Ok(true);
+                    }
+                    Err(error) => error.to_string(),
+                }
+            }
+            Ok(Err(error)) => try_extract_panic_message(error),
         };
-        tracing::error!(
-            "Error occurred while processing {} job {:?}: {:?}",
-            Self::SERVICE_NAME,
-            job_id,
-            error_message
+        self.log_event(
+            LogLevel::ERROR,
+            &format!(
+                "Error occurred while processing {} job {:?}: {:?}",
+                Self::SERVICE_NAME,
+                job_id,
+                error_message
+            ),
         );
 
-        self.save_failure(job_id, started_at, error_message).await;
-        Ok(())
+        self.on_job_finished(started_at, false);
+
+        if attempt < Self::MAX_ATTEMPTS {
+            self.log_event(
+                LogLevel::INFO,
+                &format!(
+                    "Retrying {} job {:?} (attempt {attempt} of {} failed)",
+                    Self::SERVICE_NAME,
+                    job_id,
+                    Self::MAX_ATTEMPTS
+                ),
+            );
+            self.mark_for_retry(job_id, started_at, attempt, error_message)
+                .await
+                .context("mark_for_retry()")?;
+            Span::current().record("outcome", "retried");
+            return Ok(true);
+        }
+
+        self.save_failure(job_id.clone(), started_at, error_message.clone())
+            .await;
+        self.publish_outcome(JobOutcome::Failure {
+            job_id,
+            error: error_message,
+        });
+        Span::current().record("outcome", "failed");
+        Ok(true)
+    }
+
+    /// Returns a channel to which outcomes of processed jobs are published after they are saved,
+    /// e.g. to forward them to a message bus. Publishing never blocks; if the channel is full,
+    /// the outcome is dropped with a warning. By default, outcomes are not published.
+    fn outcome_sink(&self) -> Option<mpsc::Sender<JobOutcome<Self::JobId>>> {
+        None
+    }
+
+    /// Publishes a job outcome to [`Self::outcome_sink()`], if any.
+    fn publish_outcome(&self, outcome: JobOutcome<Self::JobId>) {
+        let Some(sink) = self.outcome_sink() else {
+            return;
+        };
+        if let Err(err) = sink.try_send(outcome) {
+            let reason = match &err {
+                mpsc::error::TrySendError::Full(_) => "sink is full",
+                mpsc::error::TrySendError::Closed(_) => "sink is closed",
+            };
+            self.log_event(
+                LogLevel::WARN,
+                &format!(
+                    "Dropped {} job outcome {:?}: {reason}",
+                    Self::SERVICE_NAME,
+                    err.into_inner()
+                ),
+            );
+        }
     }
 
     /// Invoked when `process_job` doesn't panic
@@ -166,8 +832,898 @@ pub trait JobProcessor: Sync + Send {
         artifacts: Self::JobArtifacts,
     ) -> anyhow::Result<()>;
 
+    /// Invoked when the task spawned by `process_job` returns a [`RequeueJob`] error.
+    /// Should put the job back into the queue so that it can be picked up by another worker.
+    ///
+    /// The default implementation doesn't support requeueing and leaves the job in progress, so that it's returned
+    /// by `get_next_job` again once it's reset by the stuck job requeuer (e.g., `requeue_stuck_jobs()` in the DAL).
+    async fn requeue(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        reason: String,
+    ) -> anyhow::Result<()> {
+        let _ = started_at;
+        self.log_event(
+            LogLevel::WARN,
+            &format!(
+                "Job requeue is not supported by {}; {:?} is left in progress: {reason}",
+                Self::SERVICE_NAME,
+                job_id
+            ),
+        );
+        Ok(())
+    }
+
+    /// Invoked when a job fails and fewer than [`Self::MAX_ATTEMPTS`] attempts to process it were made
+    /// (`attempt` is the 1-based number of the failed attempt). Should put the job back into the queue,
+    /// so that it's returned by `get_next_job` again.
+    ///
+    /// The default implementation doesn't save the failure and leaves the job in progress, so that it stays retryable
+    /// and is returned by `get_next_job` again once it's reset by the stuck job requeuer (e.g., `requeue_stuck_jobs()`
+    /// in the DAL).
+    async fn mark_for_retry(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        attempt: u32,
+        error: String,
+    ) -> anyhow::Result<()> {
+        let _ = started_at;
+        self.log_event(
+            LogLevel::WARN,
+            &format!(
+                "{} job {:?} is left in progress to be retried after attempt {attempt} failed: {error}",
+                Self::SERVICE_NAME,
+                job_id
+            ),
+        );
+        Ok(())
+    }
+
     fn max_attempts(&self) -> u32;
 
     /// Invoked in `wait_for_task` for in-progress job.
     async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
+
+    /// Invoked by `run` when the processor becomes idle, i.e. `get_next_job` returns `None`
+    /// after returning a job. The processor is considered idle when `run` starts.
+    fn on_idle(&self) {}
+
+    /// Invoked by `run` when the processor becomes busy, i.e. `get_next_job` returns a job
+    /// after returning `None` (or for the first time).
+    fn on_busy(&self) {}
+
+    /// Awaits `acquire` (e.g., `pool.access_storage()`), timing how long it takes. The latency is reported
+    /// via [`Self::on_connection_acquired()`]. Implementations should use this method to acquire DB connections
+    /// in trait methods, so that connection pool contention is observable.
+    async fn acquire_connection<F, T>(&self, acquire: F) -> T
+    where
+        F: Future<Output = T> + Send,
+        T: Send,
+    {
+        let started_at = Instant::now();
+        let connection = acquire.await;
+        self.on_connection_acquired(started_at.elapsed());
+        connection
+    }
+
+    /// Invoked by [`Self::acquire_connection()`] with the connection acquisition latency.
+    /// By default, reports the latency to the `job_processor_connection_acquisition_latency` metric.
+    fn on_connection_acquired(&self, latency: Duration) {
+        METRICS.connection_acquisition_latency[&Self::SERVICE_NAME].observe(latency);
+    }
+
+    /// Invoked by `run` when a job is returned by `get_next_job`. By default, reports the job
+    /// to metrics if [`Self::EMIT_METRICS`] is set.
+    fn on_job_started(&self) {
+        if Self::EMIT_METRICS {
+            METRICS.jobs[&(Self::SERVICE_NAME, JobStage::Started)].inc();
+        }
+    }
+
+    /// Returns the time at which the job was enqueued, if known. If this returns a value, `run` reports the time
+    /// the job has spent in the queue via [`Self::observe_queue_latency()`]. By default, returns `None`.
+    fn job_enqueued_at(&self, job: &Self::Job) -> Option<SystemTime> {
+        let _ = job;
+        None
+    }
+
+    /// Invoked by `run` when a job with a known enqueue time (see [`Self::job_enqueued_at()`]) is returned
+    /// by `get_next_job`. By default, reports the queue latency to metrics if [`Self::EMIT_METRICS`] is set.
+    fn observe_queue_latency(
+        &self,
+        job_id: &Self::JobId,
+        enqueued_at: SystemTime,
+        picked_up_at: SystemTime,
+    ) {
+        let _ = job_id;
+        if Self::EMIT_METRICS {
+            let latency = picked_up_at.duration_since(enqueued_at).unwrap_or_default();
+            METRICS.queue_latency[&Self::SERVICE_NAME].observe(latency);
+        }
+    }
+
+    /// Invoked by `wait_for_task` when a job has succeeded (i.e., its result is saved) or failed, including
+    /// failures that will be retried. Requeued jobs are reported via [`Self::on_job_requeued()`] instead.
+    /// By default, reports the job outcome and duration to metrics if [`Self::EMIT_METRICS`] is set.
+    fn on_job_finished(&self, started_at: Instant, succeeded: bool) {
+        if Self::EMIT_METRICS {
+            let stage = if succeeded {
+                JobStage::Succeeded
+            } else {
+                JobStage::Failed
+            };
+            METRICS.jobs[&(Self::SERVICE_NAME, stage)].inc();
+            METRICS.duration[&Self::SERVICE_NAME].observe(started_at.elapsed());
+        }
+    }
+
+    /// Invoked by `wait_for_task` before a job is passed to [`Self::requeue()`], either because its task
+    /// has returned a [`RequeueJob`] error or because it has failed transiently. By default, reports the job
+    /// to metrics if [`Self::EMIT_METRICS`] is set; requeued jobs are not counted as failed.
+    fn on_job_requeued(&self, started_at: Instant) {
+        let _ = started_at;
+        if Self::EMIT_METRICS {
+            METRICS.jobs[&(Self::SERVICE_NAME, JobStage::Requeued)].inc();
+        }
+    }
+
+    /// Invoked for all log events emitted by the framework methods (`run` and `wait_for_task`).
+    /// By default, forwards events to `tracing` with the specified level; can be overridden
+    /// to route logs of a particular processor instance elsewhere (e.g., to tag them with the instance ID).
+    fn log_event(&self, level: LogLevel, msg: &str) {
+        match level {
+            LogLevel::ERROR => tracing::error!("{msg}"),
+            LogLevel::WARN => tracing::warn!("{msg}"),
+            LogLevel::INFO => tracing::info!("{msg}"),
+            LogLevel::DEBUG => tracing::debug!("{msg}"),
+            LogLevel::TRACE => tracing::trace!("{msg}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Jobs are durations for which the spawned task sleeps.
+    #[derive(Debug, Default)]
+    struct MockState {
+        queue: Mutex<Vec<(u32, Duration)>>,
+        script: MockScript,
+        records: MockRecords,
+    }
+
+    /// Scripted behavior of [`MockProcessor`]. By default, all jobs succeed on the first attempt.
+    #[derive(Debug, Default)]
+    struct MockScript {
+        /// Errors returned by the spawned tasks for the specified jobs. Each error is returned once, in order.
+        errors: Mutex<Vec<(u32, JobFailure)>>,
+        /// Reasons of [`RequeueJob`] errors returned by the spawned tasks for the specified jobs.
+        requeue_reasons: Mutex<HashMap<u32, String>>,
+        /// Values returned by `get_job_attempts()` for the specified jobs (1 for other jobs).
+        job_attempts: Mutex<HashMap<u32, u32>>,
+        /// Whether requeued jobs are put back into the queue.
+        return_requeued_jobs: Mutex<bool>,
+        /// Number of remaining attempts to process the specified jobs that fail with a generic error.
+        failing_attempts: Mutex<HashMap<u32, u32>>,
+        /// Delays of saving results for the specified jobs.
+        save_delays: Mutex<HashMap<u32, Duration>>,
+        /// Delay of each `get_next_job()` call.
+        next_job_delay: Mutex<Duration>,
+        /// Number of remaining `get_next_job()` calls that panic.
+        panicking_next_job_calls: Mutex<usize>,
+    }
+
+    /// Calls to [`MockProcessor`] methods recorded for assertions.
+    #[derive(Debug, Default)]
+    struct MockRecords {
+        /// Jobs and attempts passed to `mark_for_retry()`. Retried jobs are put back into the queue.
+        retries: Mutex<Vec<(u32, u32)>>,
+        results: Mutex<Vec<u32>>,
+        failures: Mutex<Vec<(u32, String)>>,
+        requeued: Mutex<Vec<(u32, String)>>,
+        /// Values returned by `pending_jobs_count()`.
+        pending_jobs_counts: Mutex<Vec<u64>>,
+        /// IDs of jobs passed to `observe_queue_latency()`.
+        queue_latency_observations: Mutex<Vec<u32>>,
+        /// IDs of jobs for which saving a delayed result has started.
+        saving: Mutex<Vec<u32>>,
+        /// Stages reported by `on_job_finished()` and `on_job_requeued()`.
+        finished: Mutex<Vec<JobStage>>,
+        /// Number of saved results at the end of each `get_next_job()` call.
+        results_at_next_job: Mutex<Vec<usize>>,
+        /// Timestamps of all `get_next_job()` calls.
+        next_job_calls: Mutex<Vec<Instant>>,
+        /// Number of `on_busy()` calls.
+        busy_count: Mutex<usize>,
+        /// Number of `on_idle()` calls.
+        idle_count: Mutex<usize>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct MockProcessor<const CONCURRENCY: usize = 1, const MAX_ATTEMPTS: u32 = 1>(Arc<MockState>);
+
+    impl<const CONCURRENCY: usize, const MAX_ATTEMPTS: u32> MockProcessor<CONCURRENCY, MAX_ATTEMPTS> {
+        fn new(jobs: impl IntoIterator<Item = (u32, Duration)>) -> (Self, Arc<MockState>) {
+            let state = Arc::new(MockState::default());
+            let mut queue: Vec<_> = jobs.into_iter().collect();
+            queue.reverse();
+            *state.queue.lock().unwrap() = queue;
+            (Self(state.clone()), state)
+        }
+
+        fn script(&self) -> &MockScript {
+            &self.0.script
+        }
+
+        fn records(&self) -> &MockRecords {
+            &self.0.records
+        }
+    }
+
+    #[async_trait]
+    impl<const CONCURRENCY: usize, const MAX_ATTEMPTS: u32> JobProcessor
+        for MockProcessor<CONCURRENCY, MAX_ATTEMPTS>
+    {
+        type Job = (u32, Duration);
+        type JobId = u32;
+        type JobArtifacts = ();
+
+        const POLLING_INTERVAL_MS: u64 = 10;
+        const MAX_CONCURRENT_JOBS: usize = CONCURRENCY;
+        const MAX_ATTEMPTS: u32 = MAX_ATTEMPTS;
+        const SERVICE_NAME: &'static str = "mock";
+        const JOB_TIMEOUT: Option<Duration> = Some(Duration::from_secs(1));
+        const SHUTDOWN_GRACE: Duration = Duration::from_millis(100);
+        const PENDING_JOBS_LOG_INTERVAL: Option<Duration> = Some(Duration::ZERO);
+
+        async fn get_next_job(&self) -> anyhow::Result<Option<(u32, (u32, Duration))>> {
+            self.records()
+                .next_job_calls
+                .lock()
+                .unwrap()
+                .push(Instant::now());
+            let should_panic = {
+                let mut panicking_calls = self.script().panicking_next_job_calls.lock().unwrap();
+                let should_panic = *panicking_calls > 0;
+                *panicking_calls = panicking_calls.saturating_sub(1);
+                should_panic
+            };
+            if should_panic {
+                panic!("get_next_job() panicked");
+            }
+            let delay = *self.script().next_job_delay.lock().unwrap();
+            sleep(delay).await;
+            let results_count = self.records().results.lock().unwrap().len();
+            self.records()
+                .results_at_next_job
+                .lock()
+                .unwrap()
+                .push(results_count);
+            let job = self.0.queue.lock().unwrap().pop();
+            Ok(job.map(|(job_id, duration)| (job_id, (job_id, duration))))
+        }
+
+        async fn pending_jobs_count(&self) -> anyhow::Result<u64> {
+            let count = self.0.queue.lock().unwrap().len() as u64;
+            self.records()
+                .pending_jobs_counts
+                .lock()
+                .unwrap()
+                .push(count);
+            Ok(count)
+        }
+
+        fn job_enqueued_at(&self, _job: &(u32, Duration)) -> Option<SystemTime> {
+            Some(SystemTime::UNIX_EPOCH)
+        }
+
+        fn observe_queue_latency(
+            &self,
+            job_id: &u32,
+            enqueued_at: SystemTime,
+            picked_up_at: SystemTime,
+        ) {
+            assert!(picked_up_at > enqueued_at);
+            self.records()
+                .queue_latency_observations
+                .lock()
+                .unwrap()
+                .push(*job_id);
+        }
+
+        async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) {
+            self.records()
+                .failures
+                .lock()
+                .unwrap()
+                .push((job_id, error));
+        }
+
+        async fn process_job(
+            &self,
+            (job_id, duration): (u32, Duration),
+            _started_at: Instant,
+        ) -> JoinHandle<anyhow::Result<()>> {
+            let error = {
+                let mut errors = self.script().errors.lock().unwrap();
+                let pos = errors.iter().position(|(id, _)| *id == job_id);
+                pos.map(|pos| errors.remove(pos).1)
+            };
+            let requeue_reason = self
+                .script()
+                .requeue_reasons
+                .lock()
+                .unwrap()
+                .remove(&job_id);
+            let should_fail = match self
+                .script()
+                .failing_attempts
+                .lock()
+                .unwrap()
+                .get_mut(&job_id)
+            {
+                Some(attempts) if *attempts > 0 => {
+                    *attempts -= 1;
+                    true
+                }
+                _ => false,
+            };
+            tokio::spawn(async move {
+                sleep(duration).await;
+                if let Some(reason) = requeue_reason {
+                    return Err(RequeueJob { reason }.into());
+                }
+                if should_fail {
+                    anyhow::bail!("attempt failed");
+                }
+                match error {
+                    Some(error) => Err(error.into()),
+                    None => Ok(()),
+                }
+            })
+        }
+
+        async fn save_result(
+            &self,
+            job_id: u32,
+            _started_at: Instant,
+            _artifacts: (),
+        ) -> anyhow::Result<()> {
+            let delay = self.script().save_delays.lock().unwrap().remove(&job_id);
+            if let Some(delay) = delay {
+                self.records().saving.lock().unwrap().push(job_id);
+                sleep(delay).await;
+            }
+            self.records().results.lock().unwrap().push(job_id);
+            Ok(())
+        }
+
+        async fn requeue(
+            &self,
+            job_id: u32,
+            _started_at: Instant,
+            reason: String,
+        ) -> anyhow::Result<()> {
+            self.records()
+                .requeued
+                .lock()
+                .unwrap()
+                .push((job_id, reason));
+            if *self.script().return_requeued_jobs.lock().unwrap() {
+                self.0.queue.lock().unwrap().push((job_id, Duration::ZERO));
+            }
+            Ok(())
+        }
+
+        async fn mark_for_retry(
+            &self,
+            job_id: u32,
+            _started_at: Instant,
+            attempt: u32,
+            _error: String,
+        ) -> anyhow::Result<()> {
+            self.records()
+                .retries
+                .lock()
+                .unwrap()
+                .push((job_id, attempt));
+            self.0.queue.lock().unwrap().push((job_id, Duration::ZERO));
+            Ok(())
+        }
+
+        fn max_attempts(&self) -> u32 {
+            3
+        }
+
+        async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
+            let attempts = self
+                .script()
+                .job_attempts
+                .lock()
+                .unwrap()
+                .get(job_id)
+                .copied();
+            Ok(attempts.unwrap_or(1))
+        }
+
+        fn on_job_finished(&self, _started_at: Instant, succeeded: bool) {
+            let stage = if succeeded {
+                JobStage::Succeeded
+            } else {
+                JobStage::Failed
+            };
+            self.records().finished.lock().unwrap().push(stage);
+        }
+
+        fn on_job_requeued(&self, _started_at: Instant) {
+            self.records()
+                .finished
+                .lock()
+                .unwrap()
+                .push(JobStage::Requeued);
+        }
+
+        fn on_busy(&self) {
+            *self.records().busy_count.lock().unwrap() += 1;
+        }
+
+        fn on_idle(&self) {
+            *self.records().idle_count.lock().unwrap() += 1;
+        }
+    }
+
+    #[tokio::test]
+    async fn hung_job_is_timed_out() {
+        let jobs = [
+            (1, Duration::from_millis(10)),
+            (2, Duration::from_secs(3_600)),
+        ];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(2)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [1]);
+        let failures = state.records.failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        let (job_id, error) = &failures[0];
+        assert_eq!(*job_id, 2);
+        assert!(error.contains("timed out after 1 seconds"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn slow_job_is_interrupted_on_shutdown() {
+        let jobs = [
+            (1, Duration::from_millis(10)),
+            (2, Duration::from_secs(3_600)),
+        ];
+        let (processor, state) = MockProcessor::<2>::new(jobs);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let run_task = tokio::spawn(processor.run(stop_receiver, None));
+
+        while state.records.results.lock().unwrap().is_empty() {
+            assert!(!run_task.is_finished());
+            sleep(Duration::from_millis(10)).await;
+        }
+        stop_sender.send_replace(true);
+        timeout(Duration::from_secs(10), run_task)
+            .await
+            .expect("job processor is hung")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [1]);
+        let failures = state.records.failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        let (job_id, error) = &failures[0];
+        assert_eq!(*job_id, 2);
+        assert!(error.contains("interrupted by shutdown"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn saving_result_is_not_interrupted_on_shutdown() {
+        let jobs = [(1, Duration::ZERO), (2, Duration::from_secs(3_600))];
+        let (processor, state) = MockProcessor::<2>::new(jobs);
+        // Saving the result takes longer than the shutdown grace period.
+        state
+            .script
+            .save_delays
+            .lock()
+            .unwrap()
+            .insert(1, Duration::from_millis(500));
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let run_task = tokio::spawn(processor.run(stop_receiver, None));
+
+        while state.records.saving.lock().unwrap().is_empty() {
+            assert!(!run_task.is_finished());
+            sleep(Duration::from_millis(10)).await;
+        }
+        stop_sender.send_replace(true);
+        timeout(Duration::from_secs(10), run_task)
+            .await
+            .expect("job processor is hung")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [1]);
+        let failures = state.records.failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        let (job_id, error) = &failures[0];
+        assert_eq!(*job_id, 2);
+        assert!(error.contains("interrupted by shutdown"), "{error}");
+        // Each job must be finished exactly once.
+        let finished = state.records.finished.lock().unwrap();
+        assert_eq!(finished.len(), 2);
+        assert!(finished.contains(&JobStage::Succeeded), "{finished:?}");
+        assert!(finished.contains(&JobStage::Failed), "{finished:?}");
+    }
+
+    #[tokio::test]
+    async fn in_flight_jobs_are_driven_while_polling_for_new_jobs() {
+        let jobs = [
+            (1, Duration::ZERO),
+            (2, Duration::from_millis(500)),
+            (3, Duration::ZERO),
+        ];
+        let (processor, state) = MockProcessor::<2>::new(jobs);
+        *state.script.next_job_delay.lock().unwrap() = Duration::from_millis(100);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(3)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        // The 1st job must be saved while polling for the 2nd job, and the 3rd job must be processed
+        // while the 2nd one is in flight.
+        assert_eq!(
+            *state.records.results_at_next_job.lock().unwrap(),
+            [0, 1, 1]
+        );
+        assert_eq!(*state.records.results.lock().unwrap(), [1, 3, 2]);
+    }
+
+    #[tokio::test]
+    async fn failures_are_routed_by_kind() {
+        let jobs = [
+            (1, Duration::ZERO),
+            (2, Duration::ZERO),
+            (3, Duration::ZERO),
+        ];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        state.script.errors.lock().unwrap().extend([
+            (1, JobFailure::Transient("deadlock".to_owned())),
+            (2, JobFailure::Permanent("malformed job".to_owned())),
+        ]);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(3)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [3]);
+        let requeued = state.records.requeued.lock().unwrap();
+        assert_eq!(requeued.len(), 1);
+        let (job_id, reason) = &requeued[0];
+        assert_eq!(*job_id, 1);
+        assert!(reason.contains("deadlock"), "{reason}");
+        assert_eq!(
+            *state.records.failures.lock().unwrap(),
+            [(2, "malformed job".to_owned())]
+        );
+        // The transiently failed job is requeued rather than counted as failed.
+        assert_eq!(
+            *state.records.finished.lock().unwrap(),
+            [JobStage::Requeued, JobStage::Failed, JobStage::Succeeded]
+        );
+    }
+
+    #[tokio::test]
+    async fn transient_failures_respect_max_attempts() {
+        let jobs = [(1, Duration::ZERO), (2, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        state.script.errors.lock().unwrap().extend([
+            (1, JobFailure::Transient("deadlock".to_owned())),
+            (2, JobFailure::Transient("deadlock".to_owned())),
+        ]);
+        // Job 2 has reached `max_attempts()`.
+        state.script.job_attempts.lock().unwrap().insert(2, 3);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(2)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        let requeued = state.records.requeued.lock().unwrap();
+        assert_eq!(requeued.len(), 1);
+        assert_eq!(requeued[0].0, 1);
+        assert_eq!(
+            *state.records.failures.lock().unwrap(),
+            [(2, "transient failure: deadlock".to_owned())]
+        );
+    }
+
+    #[tokio::test]
+    async fn transient_failures_respect_framework_max_attempts() {
+        let jobs = [(1, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1, 2>::new(jobs);
+        state.script.errors.lock().unwrap().extend([
+            (1, JobFailure::Transient("deadlock".to_owned())),
+            (1, JobFailure::Transient("deadlock".to_owned())),
+        ]);
+        *state.script.return_requeued_jobs.lock().unwrap() = true;
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(2)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(state.records.requeued.lock().unwrap().len(), 1);
+        assert_eq!(
+            *state.records.failures.lock().unwrap(),
+            [(1, "transient failure: deadlock".to_owned())]
+        );
+        assert!(state.queue.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_job_is_retried() {
+        let jobs = [(1, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1, 3>::new(jobs);
+        state.script.failing_attempts.lock().unwrap().insert(1, 2);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(3)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.retries.lock().unwrap(), [(1, 1), (1, 2)]);
+        assert_eq!(*state.records.results.lock().unwrap(), [1]);
+        assert!(state.records.failures.lock().unwrap().is_empty());
+        assert_eq!(
+            *state.records.finished.lock().unwrap(),
+            [JobStage::Failed, JobStage::Failed, JobStage::Succeeded]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_job_is_saved_after_exhausting_attempts() {
+        let jobs = [(1, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1, 3>::new(jobs);
+        state.script.failing_attempts.lock().unwrap().insert(1, 5);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(3)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.retries.lock().unwrap(), [(1, 1), (1, 2)]);
+        assert!(state.records.results.lock().unwrap().is_empty());
+        let failures = state.records.failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        let (job_id, error) = &failures[0];
+        assert_eq!(*job_id, 1);
+        assert!(error.contains("attempt failed"), "{error}");
+        assert!(state.queue.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn job_attempts_are_reset_after_success() {
+        let mut jobs = JobTracker::<u32>::new();
+        assert_eq!(jobs.next_attempt(&1), 1);
+        jobs.record(1, 1, true, 3);
+        assert_eq!(jobs.next_attempt(&1), 2);
+        jobs.record(1, 2, false, 3);
+        assert_eq!(jobs.next_attempt(&1), 1);
+        assert_eq!(jobs.consecutive_failures, 0);
+
+        // Attempts are also reset once they are exhausted.
+        for attempt in 1..=3 {
+            jobs.record(1, attempt, true, 3);
+        }
+        assert_eq!(jobs.next_attempt(&1), 1);
+        assert_eq!(jobs.consecutive_failures, 3);
+    }
+
+    #[tokio::test]
+    async fn requeued_job_is_finished() {
+        let jobs = [(1, Duration::ZERO), (2, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        state
+            .script
+            .requeue_reasons
+            .lock()
+            .unwrap()
+            .insert(1, "wrong shard".to_owned());
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(2)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [2]);
+        assert_eq!(
+            *state.records.requeued.lock().unwrap(),
+            [(1, "wrong shard".to_owned())]
+        );
+        assert!(state.records.failures.lock().unwrap().is_empty());
+        // Requeued jobs must not be counted as failed.
+        assert_eq!(
+            *state.records.finished.lock().unwrap(),
+            [JobStage::Requeued, JobStage::Succeeded]
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_jobs_and_queue_latency_are_reported() {
+        let jobs = [(1, Duration::ZERO), (2, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run(stop_receiver, Some(2)),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [1, 2]);
+        assert_eq!(*state.records.pending_jobs_counts.lock().unwrap(), [2, 1]);
+        assert_eq!(
+            *state.records.queue_latency_observations.lock().unwrap(),
+            [1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn busy_and_idle_are_reported_on_transitions() {
+        async fn wait_for(condition: impl Fn() -> bool) {
+            while !condition() {
+                sleep(Duration::from_millis(5)).await;
+            }
+        }
+
+        let jobs = [(1, Duration::ZERO), (2, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        let counts = || {
+            let busy_count = *state.records.busy_count.lock().unwrap();
+            (busy_count, *state.records.idle_count.lock().unwrap())
+        };
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let processor_task = tokio::spawn(processor.run(stop_receiver, None));
+
+        let test = async {
+            // Processing several jobs in a row is a single busy period.
+            wait_for(|| counts() == (1, 1)).await;
+            assert_eq!(*state.records.results.lock().unwrap(), [1, 2]);
+            // Polling an empty queue doesn't report idleness again.
+            let next_job_calls = state.records.next_job_calls.lock().unwrap().len();
+            wait_for(|| state.records.next_job_calls.lock().unwrap().len() > next_job_calls + 1)
+                .await;
+            assert_eq!(counts(), (1, 1));
+
+            state.queue.lock().unwrap().push((3, Duration::ZERO));
+            wait_for(|| counts() == (2, 2)).await;
+            assert_eq!(*state.records.results.lock().unwrap(), [1, 2, 3]);
+        };
+        timeout(Duration::from_secs(10), test)
+            .await
+            .expect("job processor is hung");
+
+        stop_sender.send_replace(true);
+        processor_task.await.unwrap().unwrap();
+        assert_eq!(counts(), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn processing_loop_is_restarted_after_panics() {
+        let jobs = [(1, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        *state.script.panicking_next_job_calls.lock().unwrap() = 2;
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        timeout(
+            Duration::from_secs(10),
+            processor.run_supervised(stop_receiver, Some(1), 3),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap();
+
+        assert_eq!(*state.records.results.lock().unwrap(), [1]);
+        // The loop has panicked twice, so it's restarted twice, with the backoff doubled after the first restart.
+        let next_job_calls = state.records.next_job_calls.lock().unwrap().clone();
+        assert_eq!(next_job_calls.len(), 3);
+        let polling_interval = Duration::from_millis(MockProcessor::<1>::POLLING_INTERVAL_MS);
+        let first_backoff = next_job_calls[1] - next_job_calls[0];
+        assert!(first_backoff >= polling_interval, "{first_backoff:?}");
+        let second_backoff = next_job_calls[2] - next_job_calls[1];
+        assert!(second_backoff >= polling_interval * 2, "{second_backoff:?}");
+    }
+
+    #[tokio::test]
+    async fn processing_loop_restarts_are_limited() {
+        let jobs = [(1, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        *state.script.panicking_next_job_calls.lock().unwrap() = 2;
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+
+        let err = timeout(
+            Duration::from_secs(10),
+            processor.run_supervised(stop_receiver, Some(1), 1),
+        )
+        .await
+        .expect("job processor is hung")
+        .unwrap_err();
+
+        let err = err.to_string();
+        assert!(err.contains("after 1 restart(s)"), "{err}");
+        assert!(err.contains("get_next_job() panicked"), "{err}");
+        assert_eq!(state.records.next_job_calls.lock().unwrap().len(), 2);
+        assert!(state.records.results.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn processing_single_job() {
+        let jobs = [(1, Duration::ZERO), (2, Duration::ZERO)];
+        let (processor, state) = MockProcessor::<1>::new(jobs);
+        state
+            .script
+            .errors
+            .lock()
+            .unwrap()
+            .push((2, JobFailure::Permanent("malformed job".to_owned())));
+
+        let (job_id, result) = processor.process_single_job().await.unwrap().unwrap();
+        assert_eq!(job_id, 1);
+        result.unwrap();
+        let (job_id, result) = processor.process_single_job().await.unwrap().unwrap();
+        assert_eq!(job_id, 2);
+        let err = result.unwrap_err();
+        assert!(err.contains("malformed job"), "{err}");
+        assert!(processor.process_single_job().await.unwrap().is_none());
+
+        // Outcomes must not be saved.
+        assert!(state.records.results.lock().unwrap().is_empty());
+        assert!(state.records.failures.lock().unwrap().is_empty());
+    }
 }
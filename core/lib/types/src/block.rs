@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use zksync_basic_types::{H2048, H256, U256};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_system_constants::SYSTEM_BLOCK_INFO_BLOCK_NUMBER_MULTIPLIER;
-use zksync_utils::concat_and_hash;
+use zksync_utils::{bytecode::hash_bytecode, concat_and_hash};
 
 use crate::{
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
@@ -27,6 +27,17 @@ impl DeployedContract {
             bytecode,
         }
     }
+
+    /// Returns the hash of the contract bytecode.
+    pub fn bytecode_hash(&self) -> H256 {
+        hash_bytecode(&self.bytecode)
+    }
+
+    /// Checks whether this and `other` contracts have the same bytecode, regardless of their addresses.
+    /// Unlike [`Self::bytecode_hash()`], doesn't panic if the bytecode is malformed.
+    pub fn bytecode_eq(&self, other: &DeployedContract) -> bool {
+        self.bytecode == other.bytecode
+    }
 }
 
 /// Holder for the block metadata that is not available from transactions themselves.
@@ -316,4 +327,15 @@ mod tests {
         assert_eq!(block_number, unpacked_block_number);
         assert_eq!(block_timestamp, unpacked_block_timestamp);
     }
+
+    #[test]
+    fn comparing_malformed_bytecodes() {
+        let first = DeployedContract::new(AccountTreeId::new(Address::repeat_byte(1)), vec![1; 33]);
+        let second =
+            DeployedContract::new(AccountTreeId::new(Address::repeat_byte(2)), vec![1; 33]);
+        assert!(first.bytecode_eq(&second));
+
+        let third = DeployedContract::new(AccountTreeId::new(Address::repeat_byte(1)), vec![2; 33]);
+        assert!(!first.bytecode_eq(&third));
+    }
 }
@@ -12,7 +12,7 @@ pub mod writes;
 
 pub use log::*;
 pub use zksync_system_constants::*;
-use zksync_utils::address_to_h256;
+use zksync_utils::{address_to_h256, u256_to_h256};
 
 /// Typed fully qualified key of the storage slot in global state tree.
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
@@ -97,6 +97,18 @@ pub fn get_is_account_key(account: &Address) -> StorageKey {
     StorageKey::new(deployer, key)
 }
 
+/// Returns the key of the slot in the `IMMUTABLE_SIMULATOR_STORAGE_ADDRESS` storage that holds the immutable
+/// with the specified `index` of the `contract`.
+pub fn immutable_simulator_storage_key(contract: Address, index: U256) -> H256 {
+    // Immutables are stored in the `mapping(uint256 => mapping(uint256 => bytes32))` mapping
+    // inside the ImmutableSimulator system contract, keyed by the contract address and then by the index.
+    let contract_slot = get_address_mapping_key(&contract, H256::zero());
+    let index = u256_to_h256(index);
+    H256(keccak256(
+        &[index.as_bytes(), contract_slot.as_bytes()].concat(),
+    ))
+}
+
 pub type StorageValue = H256;
 
 pub fn get_system_context_init_logs(chain_id: L2ChainId) -> Vec<StorageLog> {
@@ -119,3 +131,20 @@ pub fn get_system_context_init_logs(chain_id: L2ChainId) -> Vec<StorageLog> {
         ),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immutable_simulator_storage_key_is_stable() {
+        // `keccak256(index . keccak256(address . 0))`, i.e., the slot of `immutables[contract][index]`
+        // for the `mapping(uint256 => mapping(uint256 => bytes32))` at the zero slot.
+        let contract = Address::from_low_u64_be(0x1234);
+        let key = immutable_simulator_storage_key(contract, U256::from(5));
+        let expected_key: H256 = "dcaa16e06a57e6b56489509f02353438592134fcc0c59e90473c66b9bb2f9c3f"
+            .parse()
+            .unwrap();
+        assert_eq!(key, expected_key);
+    }
+}
@@ -1,8 +1,15 @@
-use std::path::PathBuf;
+use std::{
+    collections::{hash_map::Entry, BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+    thread,
+};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use zksync_basic_types::{AccountTreeId, Address, U256};
-use zksync_contracts::{read_sys_contract_bytecode, ContractLanguage, SystemContractsRepo};
+use zksync_contracts::{
+    load_sys_contract, read_sys_contract_bytecode, ContractLanguage, SystemContractsRepo,
+};
 use zksync_system_constants::{
     BOOTLOADER_UTILITIES_ADDRESS, COMPRESSOR_ADDRESS, EVENT_WRITER_ADDRESS,
 };
@@ -24,7 +31,17 @@ use crate::{
 pub const TX_NONCE_INCREMENT: U256 = U256([1, 0, 0, 0]); // 1
 pub const DEPLOYMENT_NONCE_INCREMENT: U256 = U256([0, 0, 1, 0]); // 2^128
 
-static SYSTEM_CONTRACT_LIST: [(&str, &str, Address, ContractLanguage); 21] = [
+/// Packs the transaction and deployment nonces of an account into the value stored
+/// in the NONCE_HOLDER_ADDRESS's storage.
+pub fn initial_nonce_value(tx_nonce: u64, deployment_nonce: u64) -> U256 {
+    TX_NONCE_INCREMENT * tx_nonce + DEPLOYMENT_NONCE_INCREMENT * deployment_nonce
+}
+
+// Note: the order of contracts in this list is part of the public API (see `system_contract_index()`).
+// New contracts must be appended to the end of the list, and existing entries must not be reordered.
+const SYSTEM_CONTRACT_COUNT: usize = 21;
+
+static SYSTEM_CONTRACT_LIST: [(&str, &str, Address, ContractLanguage); SYSTEM_CONTRACT_COUNT] = [
     (
         "",
         "AccountCodeStorage",
@@ -145,19 +162,163 @@ static SYSTEM_CONTRACT_LIST: [(&str, &str, Address, ContractLanguage); 21] = [
     ),
 ];
 
-static SYSTEM_CONTRACTS: Lazy<Vec<DeployedContract>> = Lazy::new(|| {
-    SYSTEM_CONTRACT_LIST
-        .iter()
-        .map(|(path, name, address, contract_lang)| DeployedContract {
+/// System contracts from `SYSTEM_CONTRACT_LIST` with the same indices; each contract is read on first access.
+static SYSTEM_CONTRACTS: [OnceCell<Arc<DeployedContract>>; SYSTEM_CONTRACT_COUNT] = {
+    const EMPTY_CELL: OnceCell<Arc<DeployedContract>> = OnceCell::new();
+    [EMPTY_CELL; SYSTEM_CONTRACT_COUNT]
+};
+
+fn load_system_contract(index: usize) -> Arc<DeployedContract> {
+    let contract = SYSTEM_CONTRACTS[index].get_or_init(|| {
+        let (path, name, address, contract_lang) = &SYSTEM_CONTRACT_LIST[index];
+        Arc::new(DeployedContract {
             account_id: AccountTreeId::new(*address),
             bytecode: read_sys_contract_bytecode(path, name, contract_lang.clone()),
         })
-        .collect::<Vec<_>>()
-});
+    });
+    contract.clone()
+}
 
 /// Gets default set of system contracts, based on ZKSYNC_HOME environment variable.
-pub fn get_system_smart_contracts() -> Vec<DeployedContract> {
-    SYSTEM_CONTRACTS.clone()
+///
+/// Contracts are cached and shared, so that contract bytecodes are not cloned on each call.
+pub fn get_system_smart_contracts() -> Vec<Arc<DeployedContract>> {
+    // Contracts that are not cached yet are read concurrently, since on a cold start (e.g., with a network
+    // file system) reading them one by one can take a significant share of the startup time.
+    thread::scope(|scope| {
+        for (index, contract) in SYSTEM_CONTRACTS.iter().enumerate() {
+            if contract.get().is_none() {
+                scope.spawn(move || load_system_contract(index));
+            }
+        }
+    });
+    (0..SYSTEM_CONTRACT_COUNT)
+        .map(load_system_contract)
+        .collect()
+}
+
+/// Reads all system contracts concurrently, based on ZKSYNC_HOME environment variable, and caches them.
+///
+/// Subsequent calls to [`get_system_smart_contracts()`] (and other functions in this module) don't touch the disk;
+/// thus, this function can be called on node startup to warm up the cache.
+pub fn preload_system_contracts() {
+    get_system_smart_contracts();
+}
+
+/// Gets the system contract deployed at `address`, based on ZKSYNC_HOME environment variable. Unlike
+/// [`get_system_smart_contracts()`], only reads the bytecode of the requested contract.
+pub fn get_system_contract_by_address(address: Address) -> Option<DeployedContract> {
+    let index = system_contract_index(address)?;
+    Some(DeployedContract::clone(&load_system_contract(index)))
+}
+
+/// Same as [`get_system_smart_contracts()`], but replaces bytecodes of the system contracts deployed
+/// at addresses in `overrides` (e.g., with patched versions of the contracts). Overrides for addresses
+/// not belonging to system contracts are ignored.
+pub fn get_system_smart_contracts_with_overrides(
+    overrides: &HashMap<Address, Vec<u8>>,
+) -> Vec<DeployedContract> {
+    get_system_smart_contracts()
+        .into_iter()
+        .map(
+            |contract| match overrides.get(contract.account_id.address()) {
+                Some(bytecode) => DeployedContract {
+                    account_id: contract.account_id,
+                    bytecode: bytecode.clone(),
+                },
+                None => DeployedContract::clone(&contract),
+            },
+        )
+        .collect()
+}
+
+/// Returns addresses of all system contracts. Unlike other functions in this module, doesn't read contract bytecodes,
+/// so it can be used to check that system contract addresses stay the same across protocol upgrades.
+pub fn system_contract_addresses() -> BTreeSet<Address> {
+    SYSTEM_CONTRACT_LIST
+        .iter()
+        .map(|(_, _, address, _)| *address)
+        .collect()
+}
+
+static SYSTEM_CONTRACT_REGISTRY: Lazy<Vec<(&'static str, Address)>> = Lazy::new(|| {
+    SYSTEM_CONTRACT_LIST
+        .iter()
+        .map(|(_, name, address, _)| (*name, *address))
+        .collect()
+});
+
+/// Returns `(name, address)` pairs for all system contracts (including precompiles and empty contracts
+/// deployed at the zero and bootloader addresses) in the same order as in [`get_system_smart_contracts()`].
+/// Like [`system_contract_addresses()`], doesn't read contract bytecodes, so it can be used for introspection
+/// (e.g., to display which system contract is deployed at a certain address).
+pub fn system_contract_registry() -> &'static [(&'static str, Address)] {
+    &SYSTEM_CONTRACT_REGISTRY
+}
+
+/// Returns the canonical index of the system contract deployed at `address`, i.e., its position
+/// in [`get_system_smart_contracts()`], or `None` if there's no system contract at this address.
+///
+/// The index is stable across versions: new system contracts are only appended to the list,
+/// so ordering-sensitive consumers (e.g., genesis) can rely on it.
+pub fn system_contract_index(address: Address) -> Option<usize> {
+    SYSTEM_CONTRACT_LIST
+        .iter()
+        .position(|(_, _, contract_address, _)| *contract_address == address)
+}
+
+/// Reads only the system contracts with the specified names, based on ZKSYNC_HOME environment variable.
+/// Contracts are returned in the same order as in [`get_system_smart_contracts()`].
+///
+/// # Panics
+///
+/// Panics if any of `names` doesn't correspond to a system contract.
+pub fn minimal_system_contracts(names: &[&str]) -> Vec<DeployedContract> {
+    for name in names {
+        assert!(
+            SYSTEM_CONTRACT_LIST
+                .iter()
+                .any(|(_, contract_name, _, _)| contract_name == name),
+            "Unknown system contract: {name}"
+        );
+    }
+    SYSTEM_CONTRACT_LIST
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, name, _, _))| names.contains(name))
+        .map(|(index, _)| DeployedContract::clone(&load_system_contract(index)))
+        .collect()
+}
+
+/// Builds an index of functions declared in ABIs of all Solidity system contracts, based on ZKSYNC_HOME
+/// environment variable. The index maps each function selector to `(contract_name, function_signature)`,
+/// e.g. `"transfer(address,uint256)"`.
+///
+/// If several contracts declare functions with the same selector, the contract appearing first
+/// in [`get_system_smart_contracts()`] wins.
+pub fn system_contracts_abi_index() -> HashMap<[u8; 4], (String, String)> {
+    let mut index = HashMap::new();
+    let mut processed_contracts = BTreeSet::new();
+    for (path, name, _, contract_lang) in &SYSTEM_CONTRACT_LIST {
+        let has_abi = path.is_empty() && matches!(contract_lang, ContractLanguage::Sol);
+        if !has_abi || !processed_contracts.insert(*name) {
+            continue;
+        }
+        let contract = load_sys_contract(name);
+        for function in contract.functions() {
+            let Entry::Vacant(entry) = index.entry(function.short_signature()) else {
+                continue;
+            };
+            let input_types: Vec<_> = function
+                .inputs
+                .iter()
+                .map(|param| param.kind.to_string())
+                .collect();
+            let signature = format!("{}({})", function.name, input_types.join(","));
+            entry.insert((name.to_string(), signature));
+        }
+    }
+    index
 }
 
 /// Loads system contracts from a given directory.
@@ -171,3 +332,47 @@ pub fn get_system_smart_contracts_from_dir(path: PathBuf) -> Vec<DeployedContrac
         })
         .collect::<Vec<_>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_contracts_follow_canonical_order() {
+        let contracts = get_system_smart_contracts();
+        assert_eq!(contracts.len(), SYSTEM_CONTRACT_COUNT);
+        let registry = system_contract_registry();
+
+        for (index, (_, name, address, _)) in SYSTEM_CONTRACT_LIST.iter().enumerate() {
+            assert_eq!(contracts[index].account_id.address(), address, "{name}");
+            assert_eq!(registry[index], (*name, *address));
+            assert_eq!(system_contract_index(*address), Some(index), "{name}");
+
+            let contract = get_system_contract_by_address(*address).unwrap();
+            assert_eq!(contract, *contracts[index], "{name}");
+        }
+        assert_eq!(
+            get_system_contract_by_address(Address::repeat_byte(0xfe)),
+            None
+        );
+    }
+
+    #[test]
+    fn minimal_system_contracts_are_ordered() {
+        let contracts = minimal_system_contracts(&["NonceHolder", "AccountCodeStorage"]);
+        let addresses: Vec<_> = contracts
+            .iter()
+            .map(|contract| *contract.account_id.address())
+            .collect();
+        assert_eq!(
+            addresses,
+            [ACCOUNT_CODE_STORAGE_ADDRESS, NONCE_HOLDER_ADDRESS]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown system contract: Unknown")]
+    fn minimal_system_contracts_reject_unknown_names() {
+        minimal_system_contracts(&["NonceHolder", "Unknown"]);
+    }
+}
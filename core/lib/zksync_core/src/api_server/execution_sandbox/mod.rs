@@ -1,6 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
-use multivm::vm_latest::utils::fee::derive_base_fee_and_gas_per_pubdata;
+use multivm::vm_latest::utils::fee::derive_gas_per_pubdata;
 use tokio::runtime::Handle;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
 use zksync_state::{PostgresStorage, PostgresStorageCaches, ReadStorage, StorageView};
@@ -146,9 +146,8 @@ pub(super) fn adjust_l1_gas_price_for_tx(
     fair_l2_gas_price: u64,
     tx_gas_per_pubdata_limit: U256,
 ) -> u64 {
-    let (_, current_pubdata_price) =
-        derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
-    if U256::from(current_pubdata_price) <= tx_gas_per_pubdata_limit {
+    let current_pubdata_price = derive_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+    if current_pubdata_price <= tx_gas_per_pubdata_limit {
         // The current pubdata price is small enough
         l1_gas_price
     } else {
@@ -13,7 +13,7 @@ use multivm::{
     vm_latest::{
         constants::{BLOCK_GAS_LIMIT, MAX_PUBDATA_PER_BLOCK},
         utils::{
-            fee::derive_base_fee_and_gas_per_pubdata,
+            fee::{derive_base_fee_and_gas_per_pubdata, derive_gas_per_pubdata},
             overhead::{derive_overhead, OverheadCoefficients},
         },
     },
@@ -144,9 +144,9 @@ pub struct TxSenderBuilder {
     rate_limiter: Option<TxSenderRateLimiter>,
     /// Proxy to submit transactions to the network. If not set, `master_connection_pool` must be set.
     proxy: Option<TxProxy>,
-    /// Actual state keeper configuration, required for tx verification.
+    /// Sealer built from the actual state keeper configuration, required for tx verification.
     /// If not set, transactions would not be checked against seal criteria.
-    state_keeper_config: Option<StateKeeperConfig>,
+    sealer: Option<ConditionalSealer>,
 }
 
 impl TxSenderBuilder {
@@ -157,7 +157,7 @@ impl TxSenderBuilder {
             master_connection_pool: None,
             rate_limiter: None,
             proxy: None,
-            state_keeper_config: None,
+            sealer: None,
         }
     }
 
@@ -182,8 +182,8 @@ impl TxSenderBuilder {
         self
     }
 
-    pub fn with_state_keeper_config(mut self, state_keeper_config: StateKeeperConfig) -> Self {
-        self.state_keeper_config = Some(state_keeper_config);
+    pub(crate) fn with_sealer(mut self, sealer: ConditionalSealer) -> Self {
+        self.sealer = Some(sealer);
         self
     }
 
@@ -207,7 +207,7 @@ impl TxSenderBuilder {
             api_contracts,
             rate_limiter: self.rate_limiter,
             proxy: self.proxy,
-            state_keeper_config: self.state_keeper_config,
+            sealer: self.sealer,
             vm_concurrency_limiter,
             storage_caches,
         }))
@@ -261,10 +261,10 @@ pub struct TxSenderInner<G> {
     rate_limiter: Option<TxSenderRateLimiter>,
     /// Optional transaction proxy to be used for transaction submission.
     pub(super) proxy: Option<TxProxy>,
-    /// An up-to-date version of the state keeper config.
+    /// Sealer built from an up-to-date version of the state keeper config.
     /// This field may be omitted on the external node, since the configuration may change unexpectedly.
     /// If this field is set to `None`, `TxSender` will assume that any transaction is executable.
-    state_keeper_config: Option<StateKeeperConfig>,
+    sealer: Option<ConditionalSealer>,
     /// Used to limit the amount of VMs that can be executed simultaneously.
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
@@ -458,13 +458,11 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         }
 
         let l1_gas_price = self.0.l1_gas_price_source.estimate_effective_gas_price();
-        let (_, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
-            l1_gas_price,
-            self.0.sender_config.fair_l2_gas_price,
-        );
+        let gas_per_pubdata_byte =
+            derive_gas_per_pubdata(l1_gas_price, self.0.sender_config.fair_l2_gas_price);
         let effective_gas_per_pubdata = cmp::min(
             tx.common_data.fee.gas_per_pubdata_limit,
-            gas_per_pubdata_byte.into(),
+            gas_per_pubdata_byte,
         );
 
         let intrinsic_consts = get_intrinsic_constants();
@@ -884,7 +882,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         tx_metrics: &TransactionExecutionMetrics,
         log_message: bool,
     ) -> Result<(), SubmitTxError> {
-        let Some(sk_config) = &self.0.state_keeper_config else {
+        let Some(sealer) = &self.0.sealer else {
             // No config provided, so we can't check if transaction satisfies the seal criteria.
             // We assume that it's executable, and if it's not, it will be caught by the main server
             // (where this check is always performed).
@@ -904,9 +902,7 @@ impl<G: L1GasPriceProvider> TxSender<G> {
         // still reject them as it's not.
         let protocol_version = ProtocolVersionId::latest();
         let seal_data = SealData::for_transaction(transaction, tx_metrics, protocol_version);
-        if let Some(reason) =
-            ConditionalSealer::find_unexecutable_reason(sk_config, &seal_data, protocol_version)
-        {
+        if let Some(reason) = sealer.find_unexecutable_reason(&seal_data, protocol_version) {
             let message = format!(
                 "Tx is Unexecutable because of {reason}; inputs for decision: {seal_data:?}"
             );
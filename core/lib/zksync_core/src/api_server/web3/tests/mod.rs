@@ -133,7 +133,8 @@ async fn spawn_server(
         gas_adjuster,
         storage_caches,
     )
-    .await;
+    .await
+    .unwrap();
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let server_builder = match transport {
@@ -116,7 +116,9 @@ impl JobProcessor for BasicWitnessInputProducer {
     const SERVICE_NAME: &'static str = "basic_witness_input_producer";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut connection = self.connection_pool.access_storage().await?;
+        let mut connection = self
+            .acquire_connection(self.connection_pool.access_storage())
+            .await?;
         let l1_batch_to_process = connection
             .basic_witness_input_producer_dal()
             .get_next_basic_witness_input_producer_job()
@@ -127,8 +129,7 @@ impl JobProcessor for BasicWitnessInputProducer {
 
     async fn save_failure(&self, job_id: Self::JobId, started_at: Instant, error: String) {
         let attempts = self
-            .connection_pool
-            .access_storage()
+            .acquire_connection(self.connection_pool.access_storage())
             .await
             .unwrap()
             .basic_witness_input_producer_dal()
@@ -177,8 +178,7 @@ impl JobProcessor for BasicWitnessInputProducer {
             .upload_input_time
             .observe(upload_started_at.elapsed());
         let mut connection = self
-            .connection_pool
-            .access_storage()
+            .acquire_connection(self.connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for BasicWitnessInputProducer")?;
         let mut transaction = connection
@@ -204,8 +204,7 @@ impl JobProcessor for BasicWitnessInputProducer {
 
     async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
         let mut connection = self
-            .connection_pool
-            .access_storage()
+            .acquire_connection(self.connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for BasicWitnessInputProducer")?;
         connection
@@ -2,6 +2,8 @@
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
 
+use std::sync::Arc;
+
 use anyhow::Context as _;
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::StorageProcessor;
@@ -25,7 +27,7 @@ pub struct GenesisParams {
     pub first_validator: Address,
     pub protocol_version: ProtocolVersionId,
     pub base_system_contracts: BaseSystemContracts,
-    pub system_contracts: Vec<DeployedContract>,
+    pub system_contracts: Vec<Arc<DeployedContract>>,
     pub first_verifier_address: Address,
     pub first_l1_verifier_config: L1VerifierConfig,
 }
@@ -166,7 +168,7 @@ async fn insert_base_system_contracts_to_factory_deps(
 
 async fn insert_system_contracts(
     storage: &mut StorageProcessor<'_>,
-    contracts: &[DeployedContract],
+    contracts: &[Arc<DeployedContract>],
     chain_id: L2ChainId,
 ) {
     let system_context_init_logs = (H256::default(), get_system_context_init_logs(chain_id));
@@ -265,7 +267,7 @@ pub(crate) async fn create_genesis_l1_batch(
     chain_id: L2ChainId,
     protocol_version: ProtocolVersionId,
     base_system_contracts: &BaseSystemContracts,
-    system_contracts: &[DeployedContract],
+    system_contracts: &[Arc<DeployedContract>],
     l1_verifier_config: L1VerifierConfig,
     verifier_address: Address,
 ) {
@@ -70,7 +70,9 @@ use crate::{
         MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorModeConfig,
     },
     metrics::{InitStage, APP_METRICS},
-    state_keeper::{create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer},
+    state_keeper::{
+        create_state_keeper, ConditionalSealer, MempoolFetcher, MempoolGuard, MiniblockSealer,
+    },
 };
 
 pub mod api_server;
@@ -722,7 +724,7 @@ async fn add_state_keeper_to_task_futures<E: L1GasPriceProvider + Send + Sync +
         object_store,
         stop_receiver.clone(),
     )
-    .await;
+    .await?;
     task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = pool_builder
@@ -1032,10 +1034,17 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
     master_pool: ConnectionPool,
     l1_gas_price_provider: Arc<G>,
     storage_caches: PostgresStorageCaches,
-) -> (TxSender<G>, VmConcurrencyBarrier) {
+) -> anyhow::Result<(TxSender<G>, VmConcurrencyBarrier)> {
+    // Transitions of seal criteria are only meaningful for L1 batches built by the state keeper.
+    let sealer_config = StateKeeperConfig {
+        log_seal_criteria_transitions: false,
+        ..state_keeper_config.clone()
+    };
+    let sealer =
+        ConditionalSealer::new(sealer_config).context("failed to create conditional sealer")?;
     let mut tx_sender_builder = TxSenderBuilder::new(tx_sender_config.clone(), replica_pool)
         .with_main_connection_pool(master_pool)
-        .with_state_keeper_config(state_keeper_config.clone());
+        .with_sealer(sealer);
 
     // Add rate limiter if enabled.
     if let Some(transactions_per_sec_limit) = web3_json_config.transactions_per_sec_limit {
@@ -1053,7 +1062,7 @@ async fn build_tx_sender<G: L1GasPriceProvider>(
             storage_caches,
         )
         .await;
-    (tx_sender, vm_barrier)
+    Ok((tx_sender, vm_barrier))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1079,7 +1088,7 @@ async fn run_http_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         gas_adjuster,
         storage_caches,
     )
-    .await;
+    .await?;
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     if with_debug_namespace {
@@ -1128,7 +1137,7 @@ async fn run_ws_api<G: L1GasPriceProvider + Send + Sync + 'static>(
         gas_adjuster,
         storage_caches,
     )
-    .await;
+    .await?;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
         .build()
         .await
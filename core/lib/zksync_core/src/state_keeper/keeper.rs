@@ -461,6 +461,19 @@ impl ZkSyncStateKeeper {
                     batch_executor.rollback_last_tx().await;
                     self.io.rollback(tx).await;
                 }
+                SealResolution::Blocked => {
+                    batch_executor.rollback_last_tx().await;
+                    self.io.rollback(tx).await;
+                    // The batch cannot grow or be sealed until the blocking condition clears. Back off
+                    // instead of immediately re-executing the same transaction in a busy loop; unconditional
+                    // sealing and cancellation are still checked on the next iteration.
+                    tracing::debug!(
+                        "L1 batch #{} is blocked by seal criteria after executing transaction {tx_hash}; \
+                         waiting for {POLL_WAIT_DURATION:?}",
+                        self.io.current_l1_batch_number()
+                    );
+                    tokio::time::sleep(POLL_WAIT_DURATION).await;
+                }
                 SealResolution::Unexecutable(reason) => {
                     batch_executor.rollback_last_tx().await;
                     self.io.reject(&tx, reason).await;
@@ -493,8 +506,20 @@ impl ZkSyncStateKeeper {
             .process_one_tx(batch_executor, updates_manager, tx.clone())
             .await;
 
+        if seal_resolution == SealResolution::Blocked {
+            // `Blocked` only prevents the batch from being sealed; it doesn't concern the transaction itself.
+            // The upgrade transaction must be the first one in the batch regardless, so it's included,
+            // and the blocking condition is re-evaluated for subsequent transactions.
+            tracing::info!(
+                "Upgrade transaction {:?} is included in L1 batch #{} despite the batch being blocked \
+                 by seal criteria",
+                tx.hash(),
+                self.io.current_l1_batch_number()
+            );
+        }
+
         match &seal_resolution {
-            SealResolution::NoSeal | SealResolution::IncludeAndSeal => {
+            SealResolution::NoSeal | SealResolution::IncludeAndSeal | SealResolution::Blocked => {
                 let TxExecutionResult::Success {
                     tx_result,
                     tx_metrics,
@@ -639,6 +664,8 @@ impl ZkSyncStateKeeper {
                     gas_count: tx_gas_excluding_writes + tx_writes_l1_gas,
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
+                    execution_result: Some(tx_result.result.clone()),
+                    tx_selector: SealData::tx_selector(&tx),
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -649,6 +676,8 @@ impl ZkSyncStateKeeper {
                     cumulative_size: tx_data.cumulative_size
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
+                    execution_result: None,
+                    tx_selector: None,
                 };
 
                 if let Some(sealer) = &self.sealer {
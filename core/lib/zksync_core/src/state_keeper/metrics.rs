@@ -107,6 +107,7 @@ enum SealResolutionLabel {
     NoSeal,
     IncludeAndSeal,
     ExcludeAndSeal,
+    Blocked,
     Unexecutable,
 }
 
@@ -116,6 +117,7 @@ impl From<&SealResolution> for SealResolutionLabel {
             SealResolution::NoSeal => Self::NoSeal,
             SealResolution::IncludeAndSeal => Self::IncludeAndSeal,
             SealResolution::ExcludeAndSeal => Self::ExcludeAndSeal,
+            SealResolution::Blocked => Self::Blocked,
             SealResolution::Unexecutable(_) => Self::Unexecutable,
         }
     }
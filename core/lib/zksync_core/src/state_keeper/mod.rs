@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::{
     configs::chain::{MempoolConfig, NetworkConfig, StateKeeperConfig},
@@ -45,7 +46,7 @@ pub(crate) async fn create_state_keeper<G>(
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Box<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
-) -> ZkSyncStateKeeper
+) -> anyhow::Result<ZkSyncStateKeeper>
 where
     G: L1GasPriceProvider + 'static + Send + Sync,
 {
@@ -55,6 +56,9 @@ where
         state_keeper_config.transaction_slots,
         MAX_TXS_IN_BLOCK
     );
+    // Validate the seal criteria config before any I/O is performed.
+    let sealer = ConditionalSealer::new(state_keeper_config.clone())
+        .context("failed to create conditional sealer")?;
 
     let batch_executor_base = MainBatchExecutorBuilder::new(
         db_config.state_keeper_db_path.clone(),
@@ -79,11 +83,10 @@ where
     )
     .await;
 
-    let sealer = ConditionalSealer::new(state_keeper_config);
-    ZkSyncStateKeeper::new(
+    Ok(ZkSyncStateKeeper::new(
         stop_receiver,
         Box::new(io),
         Box::new(batch_executor_base),
         sealer,
-    )
+    ))
 }
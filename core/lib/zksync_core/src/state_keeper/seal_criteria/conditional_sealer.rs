@@ -3,14 +3,28 @@
 //! It is used on the main node to decide when the batch should be sealed (as opposed to the external node,
 //! which unconditionally follows the instructions from the main node).
 
+use anyhow::Context as _;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
-use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use super::{
+    criteria, CriterionCost, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS,
+};
+
+/// Seal resolution aggregated over all criteria of a [`ConditionalSealer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedResolution {
+    /// The strictest resolution returned by the criteria.
+    pub resolution: SealResolution,
+    /// Names of all criteria that returned a resolution with the same severity as `resolution`,
+    /// in the order of evaluation. Empty if `resolution` is [`SealResolution::NoSeal`].
+    pub contributors: Vec<&'static str>,
+}
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 ///
-/// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`].
+/// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`]
+/// (the only exception being the opt-in [`StateKeeperConfig::use_timeout_seal_criterion`]).
 /// Non-deterministic seal criteria are expressed using [`IoSealCriteria`](super::IoSealCriteria).
 #[derive(Debug)]
 pub struct ConditionalSealer {
@@ -21,16 +35,16 @@ pub struct ConditionalSealer {
 impl ConditionalSealer {
     /// Finds a reason why a transaction with the specified `data` is unexecutable.
     pub(crate) fn find_unexecutable_reason(
-        config: &StateKeeperConfig,
+        &self,
         data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str> {
-        for sealer in &Self::default_sealers() {
+        for sealer in &self.sealers {
             const MOCK_BLOCK_TIMESTAMP: u128 = 0;
             const TX_COUNT: usize = 1;
 
-            let resolution = sealer.should_seal(
-                config,
+            let (resolution, trigger) = sealer.should_seal_with_trigger(
+                &self.config,
                 MOCK_BLOCK_TIMESTAMP,
                 TX_COUNT,
                 data,
@@ -38,15 +52,35 @@ impl ConditionalSealer {
                 protocol_version,
             );
             if matches!(resolution, SealResolution::Unexecutable(_)) {
-                return Some(sealer.prom_criterion_name());
+                let trigger = trigger.unwrap_or(sealer.as_ref());
+                return Some(trigger.prom_criterion_name());
             }
         }
         None
     }
 
-    pub(crate) fn new(config: StateKeeperConfig) -> Self {
-        let sealers = Self::default_sealers();
-        Self { config, sealers }
+    /// Creates a sealer with criteria assembled from the `config`. Returns an error if the config is invalid
+    /// (e.g., contains malformed selectors).
+    pub(crate) fn new(config: StateKeeperConfig) -> anyhow::Result<Self> {
+        let mut sealers = SealCriteriaBuilder::new(&config)?.build();
+        let sealer_names: Vec<_> = sealers
+            .iter()
+            .map(|sealer| sealer.prom_criterion_name())
+            .collect();
+        tracing::info!("Using conditional seal criteria: {sealer_names:?}");
+        if config.log_seal_criteria_transitions {
+            sealers = sealers
+                .into_iter()
+                .map(|sealer| {
+                    Box::new(criteria::TransitionLoggingCriterion::new(sealer))
+                        as Box<dyn SealCriterion>
+                })
+                .collect();
+        }
+        // Evaluate cheap criteria first, so that expensive ones can be skipped (see `should_seal_l1_batch()`).
+        // The sort is stable, so the relative order of criteria with the same cost is preserved.
+        sealers.sort_by_key(|sealer| sealer.cost_hint());
+        Ok(Self { config, sealers })
     }
 
     #[cfg(test)]
@@ -66,6 +100,28 @@ impl ConditionalSealer {
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> SealResolution {
+        self.evaluate(
+            l1_batch_number,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        )
+        .resolution
+    }
+
+    /// Same as [`Self::should_seal_l1_batch()`], but also returns the criteria that produced the resolution.
+    /// If several criteria return a resolution with the winning severity, all of them are credited in metrics.
+    pub fn evaluate(
+        &self,
+        l1_batch_number: u32,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> AggregatedResolution {
         tracing::trace!(
             "Determining seal resolution for L1 batch #{l1_batch_number} with {tx_count} transactions \
              and metrics {:?}",
@@ -73,8 +129,16 @@ impl ConditionalSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut contributors = vec![];
         for sealer in &self.sealers {
-            let seal_resolution = sealer.should_seal(
+            if final_seal_resolution == SealResolution::ExcludeAndSeal
+                && sealer.cost_hint() == CriterionCost::Expensive
+            {
+                // The transaction will be excluded in any case, so there's no need to evaluate
+                // expensive criteria; the transaction will be re-evaluated in the next batch.
+                continue;
+            }
+            let (seal_resolution, trigger) = sealer.should_seal_with_trigger(
                 &self.config,
                 block_open_timestamp_ms,
                 tx_count,
@@ -82,35 +146,141 @@ impl ConditionalSealer {
                 tx_data,
                 protocol_version,
             );
-            match &seal_resolution {
-                SealResolution::IncludeAndSeal
-                | SealResolution::ExcludeAndSeal
-                | SealResolution::Unexecutable(_) => {
+            if seal_resolution == SealResolution::NoSeal {
+                continue;
+            }
+            let trigger = trigger.unwrap_or(sealer.as_ref());
+            tracing::debug!(
+                "L1 batch #{l1_batch_number} processed by `{name}` with resolution {seal_resolution:?}",
+                name = trigger.prom_criterion_name()
+            );
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                let explanation = trigger.explain(
+                    &self.config,
+                    block_open_timestamp_ms,
+                    tx_count,
+                    block_data,
+                    tx_data,
+                    protocol_version,
+                );
+                if let Some(explanation) = explanation {
                     tracing::debug!(
-                        "L1 batch #{l1_batch_number} processed by `{name}` with resolution {seal_resolution:?}",
-                        name = sealer.prom_criterion_name()
+                        "Explanation from `{name}`: {explanation}",
+                        name = trigger.prom_criterion_name()
                     );
-                    AGGREGATION_METRICS.inc(sealer.prom_criterion_name(), &seal_resolution);
                 }
-                SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            let severity = seal_resolution.severity();
+            let prev_severity = final_seal_resolution.severity();
+            final_seal_resolution = final_seal_resolution.merge(seal_resolution);
+            let new_severity = final_seal_resolution.severity();
+            if new_severity == severity {
+                if new_severity != prev_severity {
+                    contributors.clear();
+                }
+                contributors.push(trigger.prom_criterion_name());
+            }
+
+            if matches!(final_seal_resolution, SealResolution::Unexecutable(_)) {
+                // The transaction will be rejected regardless of the remaining criteria. Note that `Blocked`
+                // is not terminal: a subsequent criterion may still find the transaction unexecutable.
+                break;
+            }
+        }
+
+        for &name in &contributors {
+            AGGREGATION_METRICS.inc(name, &final_seal_resolution);
+        }
+        AggregatedResolution {
+            resolution: final_seal_resolution,
+            contributors,
+        }
+    }
+}
+
+/// Assembles seal criteria for a [`ConditionalSealer`] based on [`StateKeeperConfig`]. Optional criteria are enabled
+/// if the corresponding config values are set, and criteria listed in [`StateKeeperConfig::disabled_seal_criteria`]
+/// are removed from the built set. Criteria listed in [`StateKeeperConfig::all_of_seal_criteria`] are combined
+/// into a single criterion sealing the L1 batch only if all of them agree. Custom criteria can be added
+/// via [`Self::push()`].
+#[derive(Debug)]
+pub(crate) struct SealCriteriaBuilder<'a> {
+    config: &'a StateKeeperConfig,
+    criteria: Vec<Box<dyn SealCriterion>>,
+}
+
+impl<'a> SealCriteriaBuilder<'a> {
+    /// Returns an error if optional criteria cannot be created from the `config` (e.g., because of malformed selectors).
+    pub fn new(config: &'a StateKeeperConfig) -> anyhow::Result<Self> {
+        let mut this = Self {
+            config,
+            criteria: vec![
+                Box::new(criteria::SlotsCriterion),
+                Box::new(criteria::GasCriterion),
+                Box::new(criteria::PubDataBytesCriterion),
+                Box::new(criteria::InitialWritesCriterion),
+                Box::new(criteria::RepeatedWritesCriterion),
+                Box::new(criteria::MaxCyclesCriterion),
+                Box::new(criteria::ComputationalGasCriterion),
+                Box::new(criteria::TxEncodingSizeCriterion),
+                Box::new(criteria::L2ToL1LogsCriterion),
+            ],
+        };
+        if let Some(selectors) = &config.revert_guard_selectors {
+            let criterion = criteria::RevertGuardCriterion::new(selectors)
+                .context("invalid `revert_guard_selectors`")?;
+            this = this.push(Box::new(criterion));
+        }
+        if let Some(max_l2_to_l1_logs) = config.max_l2_to_l1_logs_per_batch {
+            this = this.push(Box::new(criteria::L2ToL1LogCountCriterion {
+                max_l2_to_l1_logs,
+            }));
+        }
+        if let Some(txs_per_block) = config.fixed_cadence_txs_per_batch {
+            this = this.push(Box::new(criteria::FixedCadenceCriterion { txs_per_block }));
+        }
+        if config.use_timeout_seal_criterion {
+            this = this.push(Box::new(criteria::TimeoutSealCriterion));
         }
-        final_seal_resolution
+        if let Some(selectors) = &config.blocked_tx_selectors {
+            let criterion = criteria::SelectorBlocklistCriterion::new(selectors)
+                .context("invalid `blocked_tx_selectors`")?;
+            this = this.push(Box::new(criterion));
+        }
+        Ok(this)
+    }
+
+    /// Adds a custom criterion. The criterion is still removed on build if it's disabled in the config.
+    pub fn push(mut self, criterion: Box<dyn SealCriterion>) -> Self {
+        self.criteria.push(criterion);
+        self
     }
 
-    fn default_sealers() -> Vec<Box<dyn SealCriterion>> {
-        vec![
-            Box::new(criteria::SlotsCriterion),
-            Box::new(criteria::GasCriterion),
-            Box::new(criteria::PubDataBytesCriterion),
-            Box::new(criteria::InitialWritesCriterion),
-            Box::new(criteria::RepeatedWritesCriterion),
-            Box::new(criteria::MaxCyclesCriterion),
-            Box::new(criteria::ComputationalGasCriterion),
-            Box::new(criteria::TxEncodingSizeCriterion),
-            Box::new(criteria::L2ToL1LogsCriterion),
-        ]
+    pub fn build(self) -> Vec<Box<dyn SealCriterion>> {
+        let disabled_criteria = self.config.disabled_seal_criteria.as_deref();
+        let disabled_criteria = disabled_criteria.unwrap_or_default();
+        let all_of_criteria = self.config.all_of_seal_criteria.as_deref();
+        let all_of_criteria = all_of_criteria.unwrap_or_default();
+
+        let (combined, mut sealers): (Vec<_>, Vec<_>) = self
+            .criteria
+            .into_iter()
+            .filter(|criterion| {
+                let name = criterion.prom_criterion_name();
+                !disabled_criteria.iter().any(|disabled| disabled == name)
+            })
+            .partition(|criterion| {
+                let name = criterion.prom_criterion_name();
+                all_of_criteria.iter().any(|combined| combined == name)
+            });
+        if !combined.is_empty() {
+            sealers.push(Box::new(criteria::CompositeSealCriterion::new(
+                "all_of",
+                criteria::CombineMode::All,
+                combined,
+            )));
+        }
+        sealers
     }
 }
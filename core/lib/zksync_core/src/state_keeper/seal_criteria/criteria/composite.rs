@@ -0,0 +1,316 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    CriterionCost, SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Mode of combining resolutions of child criteria in [`CompositeSealCriterion`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineMode {
+    /// Returns the strictest of the child resolutions, i.e., seals the L1 batch if *any* child says so.
+    Any,
+    /// Returns the least strict of the child resolutions, i.e., seals the L1 batch only if *all* children agree.
+    /// For example, if some children return `IncludeAndSeal` and others return `ExcludeAndSeal`,
+    /// the result is `IncludeAndSeal`.
+    ///
+    /// `Blocked` and `Unexecutable` resolutions are not sealing decisions, so they are propagated
+    /// if returned by any child, same as in the `Any` mode.
+    All,
+}
+
+/// [`SealCriterion`] combining several child criteria according to a [`CombineMode`].
+///
+/// [`SealCriterion::should_seal_with_trigger()`] returns the child that has determined the combined resolution,
+/// so that the resolution is reported and explained by this child rather than by the composite.
+#[derive(Debug)]
+pub struct CompositeSealCriterion {
+    name: &'static str,
+    mode: CombineMode,
+    children: Vec<Box<dyn SealCriterion>>,
+}
+
+impl CompositeSealCriterion {
+    /// Creates a composite criterion. `name` is used to refer to the composite as a whole (e.g., in logs);
+    /// resolutions are attributed to the children.
+    pub(in crate::state_keeper) fn new(
+        name: &'static str,
+        mode: CombineMode,
+        children: Vec<Box<dyn SealCriterion>>,
+    ) -> Self {
+        Self {
+            name,
+            mode,
+            children,
+        }
+    }
+
+    /// Chooses the index of the child resolution determining the combined resolution. Among several
+    /// equally strict resolutions, the first one is chosen.
+    fn choose(&self, resolutions: &[SealResolution]) -> Option<usize> {
+        let merged = resolutions.iter().cloned().reduce(SealResolution::merge)?;
+        let is_hard_gate = matches!(
+            merged,
+            SealResolution::Blocked | SealResolution::Unexecutable(_)
+        );
+        if self.mode == CombineMode::Any || is_hard_gate {
+            // `merge()` retains the first of equally strict resolutions, so this will find its index.
+            return resolutions
+                .iter()
+                .position(|resolution| *resolution == merged);
+        }
+
+        let severities = resolutions.iter().map(SealResolution::severity);
+        let min_severity = severities.clone().min()?;
+        severities
+            .enumerate()
+            .find_map(|(i, severity)| (severity == min_severity).then_some(i))
+    }
+}
+
+impl SealCriterion for CompositeSealCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let (resolution, _) = self.should_seal_with_trigger(
+            config,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        );
+        resolution
+    }
+
+    fn should_seal_with_trigger(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> (SealResolution, Option<&dyn SealCriterion>) {
+        let (mut resolutions, mut triggers): (Vec<_>, Vec<_>) = self
+            .children
+            .iter()
+            .map(|child| {
+                let (resolution, trigger) = child.should_seal_with_trigger(
+                    config,
+                    block_open_timestamp_ms,
+                    tx_count,
+                    block_data,
+                    tx_data,
+                    protocol_version,
+                );
+                (resolution, trigger.unwrap_or(child.as_ref()))
+            })
+            .unzip();
+
+        let Some(index) = self.choose(&resolutions) else {
+            return (SealResolution::NoSeal, None);
+        };
+        let resolution = resolutions.swap_remove(index);
+        let trigger = triggers.swap_remove(index);
+        if resolution == SealResolution::NoSeal {
+            (resolution, None)
+        } else {
+            (resolution, Some(trigger))
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn cost_hint(&self) -> CriterionCost {
+        self.children
+            .iter()
+            .map(|child| child.cost_hint())
+            .max()
+            .unwrap_or(CriterionCost::Cheap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FixedCriterion(&'static str, SealResolution);
+
+    impl SealCriterion for FixedCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            _tx_count: usize,
+            _block_data: &SealData,
+            _tx_data: &SealData,
+            _protocol_version: ProtocolVersionId,
+        ) -> SealResolution {
+            self.1.clone()
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    fn composite(mode: CombineMode, resolutions: &[SealResolution]) -> CompositeSealCriterion {
+        const NAMES: [&str; 3] = ["first", "second", "third"];
+
+        let children = resolutions
+            .iter()
+            .zip(NAMES)
+            .map(|(resolution, name)| {
+                Box::new(FixedCriterion(name, resolution.clone())) as Box<dyn SealCriterion>
+            })
+            .collect();
+        CompositeSealCriterion::new("composite", mode, children)
+    }
+
+    /// Returns the resolution and the name of the criterion it's attributed to.
+    fn resolve(criterion: &CompositeSealCriterion) -> (SealResolution, &'static str) {
+        let (resolution, trigger) = criterion.should_seal_with_trigger(
+            &StateKeeperConfig::default(),
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        let trigger: &dyn SealCriterion = trigger.unwrap_or(criterion);
+        (resolution, trigger.prom_criterion_name())
+    }
+
+    #[test]
+    fn combining_resolutions_in_any_mode() {
+        use SealResolution::{ExcludeAndSeal, IncludeAndSeal, NoSeal};
+
+        let test_cases = [
+            (vec![], NoSeal, "composite"),
+            (vec![NoSeal, NoSeal], NoSeal, "composite"),
+            (vec![NoSeal, IncludeAndSeal], IncludeAndSeal, "second"),
+            (
+                vec![IncludeAndSeal, ExcludeAndSeal, NoSeal],
+                ExcludeAndSeal,
+                "second",
+            ),
+            (
+                vec![ExcludeAndSeal, IncludeAndSeal, ExcludeAndSeal],
+                ExcludeAndSeal,
+                "first",
+            ),
+        ];
+        for (resolutions, expected_resolution, expected_name) in test_cases {
+            let criterion = composite(CombineMode::Any, &resolutions);
+            assert_eq!(
+                resolve(&criterion),
+                (expected_resolution, expected_name),
+                "{resolutions:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn combining_resolutions_in_all_mode() {
+        use SealResolution::{Blocked, ExcludeAndSeal, IncludeAndSeal, NoSeal, Unexecutable};
+
+        let test_cases = [
+            (vec![], NoSeal, "composite"),
+            (vec![NoSeal, ExcludeAndSeal], NoSeal, "composite"),
+            (
+                vec![ExcludeAndSeal, IncludeAndSeal],
+                IncludeAndSeal,
+                "second",
+            ),
+            (
+                vec![ExcludeAndSeal, ExcludeAndSeal],
+                ExcludeAndSeal,
+                "first",
+            ),
+            (
+                vec![IncludeAndSeal, Blocked, ExcludeAndSeal],
+                Blocked,
+                "second",
+            ),
+            (
+                vec![NoSeal, Blocked, Unexecutable("test".to_owned())],
+                Unexecutable("test".to_owned()),
+                "third",
+            ),
+        ];
+        for (resolutions, expected_resolution, expected_name) in test_cases {
+            let criterion = composite(CombineMode::All, &resolutions);
+            assert_eq!(
+                resolve(&criterion),
+                (expected_resolution, expected_name),
+                "{resolutions:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn explanation_is_taken_from_triggering_child() {
+        use crate::state_keeper::seal_criteria::criteria::SlotsCriterion;
+
+        let config = StateKeeperConfig {
+            transaction_slots: 1,
+            ..StateKeeperConfig::default()
+        };
+        let children: Vec<Box<dyn SealCriterion>> = vec![
+            Box::new(FixedCriterion("first", SealResolution::NoSeal)),
+            Box::new(SlotsCriterion),
+        ];
+        let criterion = CompositeSealCriterion::new("composite", CombineMode::Any, children);
+        let (resolution, trigger) = criterion.should_seal_with_trigger(
+            &config,
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::IncludeAndSeal);
+        let trigger = trigger.unwrap();
+        assert_eq!(trigger.prom_criterion_name(), "slots");
+        let explanation = trigger
+            .explain(
+                &config,
+                0,
+                1,
+                &SealData::default(),
+                &SealData::default(),
+                ProtocolVersionId::latest(),
+            )
+            .unwrap();
+        assert!(explanation.contains("transaction slots"), "{explanation}");
+    }
+
+    #[test]
+    fn trigger_is_taken_from_nested_composite() {
+        let inner = composite(
+            CombineMode::All,
+            &[
+                SealResolution::IncludeAndSeal,
+                SealResolution::ExcludeAndSeal,
+            ],
+        );
+        let children: Vec<Box<dyn SealCriterion>> = vec![
+            Box::new(FixedCriterion("outer", SealResolution::NoSeal)),
+            Box::new(inner),
+        ];
+        let criterion = CompositeSealCriterion::new("composite", CombineMode::Any, children);
+        assert_eq!(
+            resolve(&criterion),
+            (SealResolution::IncludeAndSeal, "first")
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Seals each L1 batch once it contains `txs_per_block` transactions. Unlike other criteria,
+/// produces deterministic batch boundaries, which is useful for tests and benchmarks. A batch that has overshot
+/// the cadence (e.g., a pending batch re-executed after a restart with a smaller `txs_per_block`) is sealed as well.
+#[derive(Debug)]
+pub struct FixedCadenceCriterion {
+    pub txs_per_block: usize,
+}
+
+impl SealCriterion for FixedCadenceCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        if tx_count >= self.txs_per_block {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "fixed_cadence"
+    }
+
+    fn explain(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        Some(format!(
+            "{tx_count} transactions in L1 batch; L1 batches are sealed each {} transactions",
+            self.txs_per_block
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig::default();
+        let criterion = FixedCadenceCriterion { txs_per_block: 3 };
+
+        for (tx_count, expected_resolution) in [
+            (1, SealResolution::NoSeal),
+            (2, SealResolution::NoSeal),
+            (3, SealResolution::IncludeAndSeal),
+            (4, SealResolution::IncludeAndSeal),
+        ] {
+            let resolution = criterion.should_seal(
+                &config,
+                0,
+                tx_count,
+                &SealData::default(),
+                &SealData::default(),
+                ProtocolVersionId::latest(),
+            );
+            assert_eq!(resolution, expected_resolution, "tx_count = {tx_count}");
+        }
+    }
+}
@@ -9,7 +9,7 @@ use zksync_types::{
 };
 
 // Local uses
-use crate::state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution};
+use crate::state_keeper::seal_criteria::{CriterionCost, SealCriterion, SealData, SealResolution};
 
 // Collected vm execution metrics should fit into geometry limits.
 // Otherwise witness generation will fail and proof won't be generated.
@@ -69,6 +69,12 @@ where
     fn prom_criterion_name(&self) -> &'static str {
         T::PROM_METRIC_CRITERION_NAME
     }
+
+    // Circuit capacity only matters for transactions that end up in the batch, so these criteria
+    // can be skipped once the transaction is known to be excluded.
+    fn cost_hint(&self) -> CriterionCost {
+        CriterionCost::Expensive
+    }
 }
 
 impl MetricExtractor for RepeatedWritesCriterion {
@@ -418,4 +424,22 @@ mod tests {
             ProtocolVersionId::Version17
         );
     }
+
+    #[test]
+    fn circuit_capacity_criteria_are_expensive() {
+        let criteria: [&dyn SealCriterion; 5] = [
+            &RepeatedWritesCriterion,
+            &InitialWritesCriterion,
+            &MaxCyclesCriterion,
+            &ComputationalGasCriterion,
+            &L2ToL1LogsCriterion,
+        ];
+        for criterion in criteria {
+            assert_eq!(
+                criterion.cost_hint(),
+                CriterionCost::Expensive,
+                "{criterion:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Caps the number of L2-to-L1 logs in an L1 batch. Unlike [`L2ToL1LogsCriterion`](super::L2ToL1LogsCriterion),
+/// the cap is set explicitly rather than derived from the circuit geometry, and it applies to all protocol versions.
+#[derive(Debug)]
+pub struct L2ToL1LogCountCriterion {
+    pub max_l2_to_l1_logs: usize,
+}
+
+impl SealCriterion for L2ToL1LogCountCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let tx_logs = tx_data.execution_metrics.l2_to_l1_logs;
+        let block_logs = block_data.execution_metrics.l2_to_l1_logs;
+        if tx_logs > self.max_l2_to_l1_logs {
+            // The transaction wouldn't fit into an empty batch either, so excluding it would stall the state keeper.
+            SealResolution::Unexecutable("Transaction emits too many L2-to-L1 logs".into())
+        } else if block_logs > self.max_l2_to_l1_logs {
+            SealResolution::ExcludeAndSeal
+        } else if block_logs == self.max_l2_to_l1_logs {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "l2_to_l1_log_count"
+    }
+
+    fn explain(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        Some(format!(
+            "{block_logs} L2-to-L1 logs in L1 batch, {tx_logs} in transaction; limit is {max} logs",
+            block_logs = block_data.execution_metrics.l2_to_l1_logs,
+            tx_logs = tx_data.execution_metrics.l2_to_l1_logs,
+            max = self.max_l2_to_l1_logs
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::tx::tx_execution_info::ExecutionMetrics;
+
+    use super::*;
+
+    fn seal_data(l2_to_l1_logs: usize) -> SealData {
+        SealData {
+            execution_metrics: ExecutionMetrics {
+                l2_to_l1_logs,
+                ..ExecutionMetrics::default()
+            },
+            ..SealData::default()
+        }
+    }
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig::default();
+        let criterion = L2ToL1LogCountCriterion {
+            max_l2_to_l1_logs: 10,
+        };
+
+        for (block_logs, tx_logs, expected_resolution) in [
+            (5, 1, SealResolution::NoSeal),
+            (10, 1, SealResolution::IncludeAndSeal),
+            (11, 2, SealResolution::ExcludeAndSeal),
+            (
+                11,
+                11,
+                SealResolution::Unexecutable("Transaction emits too many L2-to-L1 logs".into()),
+            ),
+        ] {
+            let resolution = criterion.should_seal(
+                &config,
+                0,
+                1,
+                &seal_data(block_logs),
+                &seal_data(tx_logs),
+                ProtocolVersionId::latest(),
+            );
+            assert_eq!(resolution, expected_resolution);
+        }
+    }
+}
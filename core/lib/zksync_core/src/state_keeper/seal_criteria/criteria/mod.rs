@@ -1,16 +1,47 @@
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+
+mod composite;
+mod fixed_cadence;
 mod gas;
 mod geometry_seal_criteria;
+mod l2_to_l1_log_count;
 mod pubdata_bytes;
+mod revert_guard;
+mod selector_blocklist;
 mod slots;
+mod timeout;
+mod transition_logging;
 mod tx_encoding_size;
 
 pub(in crate::state_keeper) use self::{
+    composite::{CombineMode, CompositeSealCriterion},
+    fixed_cadence::FixedCadenceCriterion,
     gas::GasCriterion,
     geometry_seal_criteria::{
         ComputationalGasCriterion, InitialWritesCriterion, L2ToL1LogsCriterion, MaxCyclesCriterion,
         RepeatedWritesCriterion,
     },
+    l2_to_l1_log_count::L2ToL1LogCountCriterion,
     pubdata_bytes::PubDataBytesCriterion,
+    revert_guard::RevertGuardCriterion,
+    selector_blocklist::SelectorBlocklistCriterion,
     slots::SlotsCriterion,
+    timeout::TimeoutSealCriterion,
+    transition_logging::TransitionLoggingCriterion,
     tx_encoding_size::TxEncodingSizeCriterion,
 };
+
+/// Parses hex-encoded 4-byte selectors (e.g., `0x08c379a0`) from the state keeper config.
+fn parse_selectors(selectors: &[String]) -> anyhow::Result<HashSet<[u8; 4]>> {
+    selectors
+        .iter()
+        .map(|selector| {
+            let bytes = hex::decode(selector.trim_start_matches("0x"))
+                .with_context(|| format!("invalid selector `{selector}`"))?;
+            <[u8; 4]>::try_from(bytes.as_slice())
+                .map_err(|_| anyhow::anyhow!("selector `{selector}` is not 4 bytes long"))
+        })
+        .collect()
+}
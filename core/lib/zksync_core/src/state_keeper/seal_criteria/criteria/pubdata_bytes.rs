@@ -7,6 +7,36 @@ use crate::state_keeper::seal_criteria::{
 #[derive(Debug)]
 pub struct PubDataBytesCriterion;
 
+impl PubDataBytesCriterion {
+    fn max_pubdata_per_l1_batch(config: &StateKeeperConfig) -> usize {
+        let max_pubdata = config
+            .max_pubdata_per_batch
+            .map_or(MAX_PUBDATA_PER_L1_BATCH, |max| {
+                max.min(MAX_PUBDATA_PER_L1_BATCH)
+            });
+        max_pubdata as usize
+    }
+
+    /// Returns pubdata sizes for the L1 batch and the transaction, respectively.
+    fn pubdata_sizes(
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> (usize, usize) {
+        let block_size =
+            block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
+        // For backward compatibility, we need to keep calculating the size of the pubdata based
+        // StorageDeduplication metrics. All vm versions
+        // after vm with virtual blocks will provide the size of the pubdata in the execution metrics.
+        let tx_size = if tx_data.execution_metrics.pubdata_published == 0 {
+            tx_data.execution_metrics.size() + tx_data.writes_metrics.size(protocol_version)
+        } else {
+            tx_data.execution_metrics.pubdata_published as usize
+        };
+        (block_size, tx_size)
+    }
+}
+
 impl SealCriterion for PubDataBytesCriterion {
     fn should_seal(
         &self,
@@ -17,22 +47,13 @@ impl SealCriterion for PubDataBytesCriterion {
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> SealResolution {
-        let max_pubdata_per_l1_batch = MAX_PUBDATA_PER_L1_BATCH as usize;
+        let max_pubdata_per_l1_batch = Self::max_pubdata_per_l1_batch(config);
         let reject_bound =
             (max_pubdata_per_l1_batch as f64 * config.reject_tx_at_eth_params_percentage).round();
         let include_and_seal_bound =
             (max_pubdata_per_l1_batch as f64 * config.close_block_at_eth_params_percentage).round();
 
-        let block_size =
-            block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
-        // For backward compatibility, we need to keep calculating the size of the pubdata based
-        // StorageDeduplication metrics. All vm versions
-        // after vm with virtual blocks will provide the size of the pubdata in the execution metrics.
-        let tx_size = if tx_data.execution_metrics.pubdata_published == 0 {
-            tx_data.execution_metrics.size() + tx_data.writes_metrics.size(protocol_version)
-        } else {
-            tx_data.execution_metrics.pubdata_published as usize
-        };
+        let (block_size, tx_size) = Self::pubdata_sizes(block_data, tx_data, protocol_version);
         if tx_size > reject_bound as usize {
             let message = "Transaction cannot be sent to L1 due to pubdata limits";
             SealResolution::Unexecutable(message.into())
@@ -48,10 +69,27 @@ impl SealCriterion for PubDataBytesCriterion {
     fn prom_criterion_name(&self) -> &'static str {
         "pub_data_size"
     }
+
+    fn explain(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        let (block_size, tx_size) = Self::pubdata_sizes(block_data, tx_data, protocol_version);
+        Some(format!(
+            "pubdata is {block_size} bytes for L1 batch, {tx_size} bytes for transaction; limit is {} bytes",
+            Self::max_pubdata_per_l1_batch(config)
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use zksync_types::tx::ExecutionMetrics;
 
     use super::*;
@@ -126,4 +164,56 @@ mod tests {
         );
         assert_eq!(full_block_resolution, SealResolution::ExcludeAndSeal);
     }
+
+    #[test]
+    fn seal_criterion_with_configured_limit() {
+        let config = StateKeeperConfig {
+            reject_tx_at_eth_params_percentage: 0.95,
+            close_block_at_eth_params_percentage: 0.9,
+            max_pubdata_per_batch: Some(1_000),
+            ..Default::default()
+        };
+        let criterion = PubDataBytesCriterion;
+        let resolve = |block_pubdata: usize, tx_pubdata: u32| {
+            let block_data = SealData {
+                execution_metrics: ExecutionMetrics {
+                    l2_l1_long_messages: block_pubdata,
+                    ..ExecutionMetrics::default()
+                },
+                ..SealData::default()
+            };
+            let tx_data = SealData {
+                execution_metrics: ExecutionMetrics {
+                    pubdata_published: tx_pubdata,
+                    ..ExecutionMetrics::default()
+                },
+                ..SealData::default()
+            };
+            criterion.should_seal(
+                &config,
+                0,
+                1,
+                &block_data,
+                &tx_data,
+                ProtocolVersionId::latest(),
+            )
+        };
+
+        assert_eq!(resolve(800, 100), SealResolution::NoSeal);
+        // Block is full.
+        assert_eq!(resolve(950, 100), SealResolution::IncludeAndSeal);
+        assert_eq!(resolve(1_001, 100), SealResolution::ExcludeAndSeal);
+        // Transaction is too large to fit into any L1 batch.
+        assert_matches!(resolve(1_001, 951), SealResolution::Unexecutable(_));
+
+        // The limit cannot be raised above the L1 commit limit.
+        let config = StateKeeperConfig {
+            max_pubdata_per_batch: Some(u64::MAX),
+            ..config
+        };
+        assert_eq!(
+            PubDataBytesCriterion::max_pubdata_per_l1_batch(&config),
+            MAX_PUBDATA_PER_L1_BATCH as usize
+        );
+    }
 }
@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use multivm::interface::{ExecutionResult, VmRevertReason};
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Excludes the transaction and seals the batch if the transaction reverted with one of the configured
+/// revert selectors (e.g., an invariant violation in a system contract). If the transaction is the first one
+/// in the batch, it's considered unexecutable instead; otherwise, it would revert in the same way in the next batch,
+/// making the state keeper seal empty batches indefinitely.
+#[derive(Debug)]
+pub struct RevertGuardCriterion {
+    selectors: HashSet<[u8; 4]>,
+}
+
+impl RevertGuardCriterion {
+    /// Creates a criterion from hex-encoded revert selectors, such as the ones
+    /// in [`StateKeeperConfig::revert_guard_selectors`].
+    /// Returns an error if any of the selectors is not a hex-encoded 4-byte value.
+    pub fn new(selectors: &[String]) -> anyhow::Result<Self> {
+        Ok(Self {
+            selectors: super::parse_selectors(selectors)?,
+        })
+    }
+
+    fn revert_selector(reason: &VmRevertReason) -> Option<[u8; 4]> {
+        let selector = match reason {
+            VmRevertReason::General { data, .. } => data.get(..4)?,
+            VmRevertReason::Unknown {
+                function_selector, ..
+            } => function_selector.as_slice(),
+            VmRevertReason::InnerTxError | VmRevertReason::VmError => return None,
+        };
+        selector.try_into().ok()
+    }
+}
+
+impl SealCriterion for RevertGuardCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let Some(ExecutionResult::Revert { output }) = &tx_data.execution_result else {
+            return SealResolution::NoSeal;
+        };
+        match Self::revert_selector(output) {
+            Some(selector) if self.selectors.contains(&selector) => {
+                if tx_count == 1 {
+                    let message = "Transaction reverted with a guarded revert selector";
+                    SealResolution::Unexecutable(message.into())
+                } else {
+                    SealResolution::ExcludeAndSeal
+                }
+            }
+            _ => SealResolution::NoSeal,
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "revert_guard"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn tx_data(result: ExecutionResult) -> SealData {
+        SealData {
+            execution_result: Some(result),
+            ..SealData::default()
+        }
+    }
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig::default();
+        let criterion = RevertGuardCriterion::new(&["0x4e487b71".to_owned()]).unwrap();
+        let should_seal = |tx_data: &SealData| {
+            criterion.should_seal(
+                &config,
+                0,
+                2,
+                &SealData::default(),
+                tx_data,
+                ProtocolVersionId::latest(),
+            )
+        };
+
+        let not_executed = SealData::default();
+        assert_eq!(should_seal(&not_executed), SealResolution::NoSeal);
+
+        let success = tx_data(ExecutionResult::Success { output: vec![] });
+        assert_eq!(should_seal(&success), SealResolution::NoSeal);
+
+        let other_revert = tx_data(ExecutionResult::Revert {
+            output: VmRevertReason::from([0xde, 0xad, 0xbe, 0xef].as_slice()),
+        });
+        assert_eq!(should_seal(&other_revert), SealResolution::NoSeal);
+
+        let guarded_revert = tx_data(ExecutionResult::Revert {
+            output: VmRevertReason::from([0x4e, 0x48, 0x7b, 0x71, 0, 1].as_slice()),
+        });
+        assert_eq!(should_seal(&guarded_revert), SealResolution::ExcludeAndSeal);
+
+        // The first transaction in a batch would revert in the same way in the next batch, so it's rejected.
+        let first_tx_resolution = criterion.should_seal(
+            &config,
+            0,
+            1,
+            &SealData::default(),
+            &guarded_revert,
+            ProtocolVersionId::latest(),
+        );
+        assert_matches!(first_tx_resolution, SealResolution::Unexecutable(_));
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Rejects transactions calling a function with one of the blocked selectors. Can be used as an operational mitigation,
+/// e.g., during an incident. Since a blocked transaction would be blocked in any batch, it's considered unexecutable
+/// (and is thus rejected by the API server as well) rather than being returned to the mempool.
+#[derive(Debug)]
+pub struct SelectorBlocklistCriterion {
+    blocked_selectors: HashSet<[u8; 4]>,
+}
+
+impl SelectorBlocklistCriterion {
+    /// Creates a criterion from hex-encoded function selectors, such as the ones
+    /// in [`StateKeeperConfig::blocked_tx_selectors`].
+    /// Returns an error if any of the selectors is not a hex-encoded 4-byte value.
+    pub fn new(selectors: &[String]) -> anyhow::Result<Self> {
+        Ok(Self {
+            blocked_selectors: super::parse_selectors(selectors)?,
+        })
+    }
+}
+
+impl SealCriterion for SelectorBlocklistCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        match &tx_data.tx_selector {
+            Some(selector) if self.blocked_selectors.contains(selector) => {
+                SealResolution::Unexecutable("Transaction selector is blocklisted".into())
+            }
+            _ => SealResolution::NoSeal,
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "selector_blocklist"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig::default();
+        let criterion = SelectorBlocklistCriterion::new(&["0xa9059cbb".to_owned()]).unwrap();
+        let should_seal = |tx_count, tx_selector| {
+            let tx_data = SealData {
+                tx_selector,
+                ..SealData::default()
+            };
+            criterion.should_seal(
+                &config,
+                0,
+                tx_count,
+                &SealData::default(),
+                &tx_data,
+                ProtocolVersionId::latest(),
+            )
+        };
+
+        for tx_count in [1, 2] {
+            assert_eq!(should_seal(tx_count, None), SealResolution::NoSeal);
+            assert_eq!(
+                should_seal(tx_count, Some([0xde, 0xad, 0xbe, 0xef])),
+                SealResolution::NoSeal
+            );
+            // Blocked transactions must be rejected rather than returned to the mempool,
+            // including the first transaction in a batch.
+            assert_matches!(
+                should_seal(tx_count, Some([0xa9, 0x05, 0x9c, 0xbb])),
+                SealResolution::Unexecutable(_)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_selectors_are_rejected() {
+        for selector in ["0xa9059c", "0xa9059cbbcc", "transfer"] {
+            let err = SelectorBlocklistCriterion::new(&[selector.to_owned()]).unwrap_err();
+            assert!(err.to_string().contains(selector), "{err}");
+        }
+    }
+}
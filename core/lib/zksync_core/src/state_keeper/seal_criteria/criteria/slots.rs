@@ -18,16 +18,30 @@ impl SealCriterion for SlotsCriterion {
         _tx_data: &SealData,
         _protocol_version: ProtocolVersionId,
     ) -> SealResolution {
-        if tx_count >= config.transaction_slots {
-            SealResolution::IncludeAndSeal
-        } else {
-            SealResolution::NoSeal
-        }
+        SealResolution::include_if(
+            tx_count >= config.transaction_slots,
+            "no transaction slots left",
+        )
     }
 
     fn prom_criterion_name(&self) -> &'static str {
         "slots"
     }
+
+    fn explain(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        Some(format!(
+            "{tx_count} transactions in L1 batch; limit is {} transaction slots",
+            config.transaction_slots
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -63,5 +77,19 @@ mod tests {
             ProtocolVersionId::latest(),
         );
         assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
+
+        let explanation = criterion.explain(
+            &config,
+            Default::default(),
+            config.transaction_slots,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        let explanation = explanation.unwrap();
+        assert_eq!(
+            explanation,
+            "2 transactions in L1 batch; limit is 2 transaction slots"
+        );
     }
 }
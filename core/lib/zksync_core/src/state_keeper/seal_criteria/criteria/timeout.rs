@@ -0,0 +1,72 @@
+use zksync_types::ProtocolVersionId;
+use zksync_utils::time::millis_since_epoch;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Seals a non-empty L1 batch once it has been open for at least `block_commit_deadline_ms`.
+///
+/// Unlike [`TimeoutSealer`](crate::state_keeper::seal_criteria::TimeoutSealer), this criterion
+/// is only checked after a transaction is executed, and it depends on the current time, so it's not deterministic.
+/// Hence, it's only used if [`StateKeeperConfig::use_timeout_seal_criterion`] is set.
+#[derive(Debug)]
+pub struct TimeoutSealCriterion;
+
+impl SealCriterion for TimeoutSealCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let block_age_ms = millis_since_epoch().saturating_sub(block_open_timestamp_ms);
+        SealResolution::include_if(
+            tx_count > 0 && block_age_ms >= u128::from(config.block_commit_deadline_ms),
+            "L1 batch commit deadline reached",
+        )
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "timeout"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig {
+            block_commit_deadline_ms: 60_000,
+            ..Default::default()
+        };
+        let now_ms = millis_since_epoch();
+        let old_block_timestamp_ms = now_ms - 120_000;
+        let fresh_block_timestamp_ms = now_ms - 1_000;
+
+        for (block_open_timestamp_ms, tx_count, expected_resolution) in [
+            (old_block_timestamp_ms, 1, SealResolution::IncludeAndSeal),
+            (old_block_timestamp_ms, 0, SealResolution::NoSeal),
+            (fresh_block_timestamp_ms, 1, SealResolution::NoSeal),
+            (now_ms + 1_000, 1, SealResolution::NoSeal),
+        ] {
+            let resolution = TimeoutSealCriterion.should_seal(
+                &config,
+                block_open_timestamp_ms,
+                tx_count,
+                &SealData::default(),
+                &SealData::default(),
+                ProtocolVersionId::latest(),
+            );
+            assert_eq!(
+                resolution, expected_resolution,
+                "block_open_timestamp_ms = {block_open_timestamp_ms}, tx_count = {tx_count}"
+            );
+        }
+    }
+}
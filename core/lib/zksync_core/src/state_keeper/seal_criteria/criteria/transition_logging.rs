@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    CriterionCost, SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Decorator for a [`SealCriterion`] that logs each time the wrapped criterion returns a resolution
+/// stricter than the one it returned previously (e.g., switches from `NoSeal` to `IncludeAndSeal`).
+/// Unlike logging every resolution, this produces at most a couple of log entries per L1 batch.
+#[derive(Debug)]
+pub struct TransitionLoggingCriterion {
+    inner: Box<dyn SealCriterion>,
+    prev_severity: AtomicU8,
+}
+
+impl TransitionLoggingCriterion {
+    pub(in crate::state_keeper) fn new(inner: Box<dyn SealCriterion>) -> Self {
+        Self {
+            inner,
+            prev_severity: AtomicU8::new(SealResolution::NoSeal.severity()),
+        }
+    }
+}
+
+impl SealCriterion for TransitionLoggingCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let (resolution, _) = self.should_seal_with_trigger(
+            config,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        );
+        resolution
+    }
+
+    fn should_seal_with_trigger(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> (SealResolution, Option<&dyn SealCriterion>) {
+        let (resolution, trigger) = self.inner.should_seal_with_trigger(
+            config,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        );
+        let severity = resolution.severity();
+        let prev_severity = self.prev_severity.swap(severity, Ordering::Relaxed);
+        if severity > prev_severity {
+            tracing::info!(
+                "Seal criterion `{}` switched to resolution {resolution:?} with {tx_count} transactions in L1 batch",
+                trigger.unwrap_or(self.inner.as_ref()).prom_criterion_name()
+            );
+        }
+        (resolution, trigger)
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        self.inner.prom_criterion_name()
+    }
+
+    fn explain(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        self.inner.explain(
+            config,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        )
+    }
+
+    fn cost_hint(&self) -> CriterionCost {
+        self.inner.cost_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_keeper::seal_criteria::criteria::SlotsCriterion;
+
+    #[test]
+    fn decorator_preserves_resolutions() {
+        let config = StateKeeperConfig {
+            transaction_slots: 2,
+            ..Default::default()
+        };
+        let criterion = TransitionLoggingCriterion::new(Box::new(SlotsCriterion));
+        assert_eq!(criterion.prom_criterion_name(), "slots");
+
+        for (tx_count, expected_resolution) in [
+            (1, SealResolution::NoSeal),
+            (2, SealResolution::IncludeAndSeal),
+            (2, SealResolution::IncludeAndSeal),
+            (1, SealResolution::NoSeal),
+        ] {
+            let resolution = criterion.should_seal(
+                &config,
+                0,
+                tx_count,
+                &SealData::default(),
+                &SealData::default(),
+                ProtocolVersionId::latest(),
+            );
+            assert_eq!(resolution, expected_resolution);
+            let severity = criterion.prev_severity.load(Ordering::Relaxed);
+            assert_eq!(severity, expected_resolution.severity());
+        }
+    }
+}
@@ -39,6 +39,23 @@ impl SealCriterion for TxEncodingSizeCriterion {
     fn prom_criterion_name(&self) -> &'static str {
         "tx_encoding_size"
     }
+
+    fn explain(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        Some(format!(
+            "encoding size is {block_size} bytes for L1 batch, {tx_size} bytes for transaction; \
+             bootloader encoding space is {BOOTLOADER_TX_ENCODING_SPACE} bytes",
+            block_size = block_data.cumulative_size,
+            tx_size = tx_data.cumulative_size
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -12,7 +12,7 @@
 
 use std::fmt;
 
-use multivm::vm_latest::TransactionVmExt;
+use multivm::{interface::ExecutionResult, vm_latest::TransactionVmExt};
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::{
     block::BlockGasCount,
@@ -44,6 +44,15 @@ pub enum SealResolution {
     /// execution is hard to predict and 2) we may have writes to the same storage slots, which will save us
     /// gas.
     ExcludeAndSeal,
+    /// Block must not be sealed until an external dependency of the block clears (e.g., a required
+    /// system transaction is executed). This is a hard gate: it overrides all other resolutions except
+    /// for `Unexecutable`, which concerns the transaction rather than the block. The latest transaction
+    /// is excluded from the block and returned to the mempool, so that the block doesn't grow
+    /// past the limits enforced by other criteria while it cannot be sealed.
+    ///
+    /// Note that this only applies to conditional criteria; unconditional sealing (e.g., on timeout)
+    /// is not affected.
+    Blocked,
     /// Unexecutable means that the last transaction of the block cannot be executed even
     /// if the block will consist of it solely. Such a transaction must be rejected.
     ///
@@ -52,21 +61,57 @@ pub enum SealResolution {
 }
 
 impl SealResolution {
-    /// Compares two seal resolutions and chooses the one that is stricter.
-    /// `Unexecutable` is stricter than `ExcludeAndSeal`.
-    /// `ExcludeAndSeal` is stricter than `IncludeAndSeal`.
-    /// `IncludeAndSeal` is stricter than `NoSeal`.
-    pub fn stricter(self, other: Self) -> Self {
+    /// Merges resolutions returned by two criteria, choosing the stricter one. The precedence is as follows:
+    ///
+    /// `Unexecutable` > `Blocked` > `ExcludeAndSeal` > `IncludeAndSeal` > `NoSeal`.
+    ///
+    /// If both resolutions are `Unexecutable`, the reason from `self` is retained.
+    pub fn merge(self, other: Self) -> Self {
         match (self, other) {
             (Self::Unexecutable(reason), _) | (_, Self::Unexecutable(reason)) => {
                 Self::Unexecutable(reason)
             }
+            (Self::Blocked, _) | (_, Self::Blocked) => Self::Blocked,
             (Self::ExcludeAndSeal, _) | (_, Self::ExcludeAndSeal) => Self::ExcludeAndSeal,
             (Self::IncludeAndSeal, _) | (_, Self::IncludeAndSeal) => Self::IncludeAndSeal,
             _ => Self::NoSeal,
         }
     }
 
+    /// Returns the strictness of this resolution consistent with [`Self::merge()`]: if `a.merge(b)`
+    /// equals `a`, then `a.severity() >= b.severity()`.
+    pub(super) fn severity(&self) -> u8 {
+        match self {
+            Self::NoSeal => 0,
+            Self::IncludeAndSeal => 1,
+            Self::ExcludeAndSeal => 2,
+            Self::Blocked => 3,
+            Self::Unexecutable(_) => 4,
+        }
+    }
+
+    /// Returns `IncludeAndSeal` if `condition` holds, and `NoSeal` otherwise. Since resolutions don't carry
+    /// sealing reasons, the `reason` is only logged.
+    pub fn include_if(condition: bool, reason: &'static str) -> Self {
+        if condition {
+            tracing::trace!("Resolved to include transaction and seal L1 batch: {reason}");
+            Self::IncludeAndSeal
+        } else {
+            Self::NoSeal
+        }
+    }
+
+    /// Returns `ExcludeAndSeal` if `condition` holds, and `NoSeal` otherwise. Since resolutions don't carry
+    /// sealing reasons, the `reason` is only logged.
+    pub fn exclude_if(condition: bool, reason: &'static str) -> Self {
+        if condition {
+            tracing::trace!("Resolved to exclude transaction and seal L1 batch: {reason}");
+            Self::ExcludeAndSeal
+        } else {
+            Self::NoSeal
+        }
+    }
+
     /// Returns `true` if L1 batch should be sealed according to this resolution.
     pub fn should_seal(&self) -> bool {
         matches!(self, Self::IncludeAndSeal | Self::ExcludeAndSeal)
@@ -81,9 +126,20 @@ pub struct SealData {
     pub(super) gas_count: BlockGasCount,
     pub(super) cumulative_size: usize,
     pub(super) writes_metrics: DeduplicatedWritesMetrics,
+    /// Outcome of the transaction execution. Only set for transaction data after the transaction
+    /// was executed by the state keeper.
+    pub(super) execution_result: Option<ExecutionResult>,
+    /// Selector of the function called by the transaction. Only set for transaction data.
+    pub(super) tx_selector: Option<[u8; 4]>,
 }
 
 impl SealData {
+    /// Extracts the selector of the function called by the `transaction`, i.e., the first 4 bytes of its calldata.
+    pub(super) fn tx_selector(transaction: &Transaction) -> Option<[u8; 4]> {
+        let selector = transaction.execute.calldata.get(..4)?;
+        selector.try_into().ok()
+    }
+
     /// Creates sealing data based on the execution of a `transaction`. Assumes that all writes
     /// performed by the transaction are initial.
     pub(crate) fn for_transaction(
@@ -95,16 +151,26 @@ impl SealData {
         let writes_metrics = DeduplicatedWritesMetrics::from_tx_metrics(tx_metrics);
         let gas_count = gas_count_from_tx_and_metrics(&transaction, &execution_metrics)
             + gas_count_from_writes(&writes_metrics, protocol_version);
+        let tx_selector = Self::tx_selector(&transaction);
         Self {
             execution_metrics,
             gas_count,
             cumulative_size: transaction.bootloader_encoding_size(),
             writes_metrics,
+            execution_result: None,
+            tx_selector,
         }
     }
 }
 
-pub(super) trait SealCriterion: fmt::Debug + Send + 'static {
+/// Relative cost of evaluating a [`SealCriterion`]. Cheap criteria are evaluated before expensive ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum CriterionCost {
+    Cheap,
+    Expensive,
+}
+
+pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
     fn should_seal(
         &self,
         config: &StateKeeperConfig,
@@ -115,9 +181,55 @@ pub(super) trait SealCriterion: fmt::Debug + Send + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Same as [`Self::should_seal()`], but also returns the criterion that has determined the resolution
+    /// if it's not `self` (e.g., a child of a criterion combining several criteria). The returned criterion
+    /// should be used to report and explain the resolution.
+    fn should_seal_with_trigger(
+        &self,
+        config: &StateKeeperConfig,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> (SealResolution, Option<&dyn SealCriterion>) {
+        let resolution = self.should_seal(
+            config,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            tx_data,
+            protocol_version,
+        );
+        (resolution, None)
+    }
+
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
+
+    /// Explains the resolution returned by [`Self::should_seal()`] for the same arguments, e.g. which limit
+    /// was approached or exceeded. This is only used for debugging, so it's fine to return `None` if
+    /// the resolution is self-explanatory.
+    fn explain(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<String> {
+        None
+    }
+
+    /// Returns the relative cost of evaluating this criterion. Expensive criteria are skipped
+    /// if the L1 batch is already known to be sealed with the latest transaction excluded.
+    ///
+    /// Criteria that can return [`SealResolution::Blocked`] must be cheap; otherwise, they may be skipped.
+    fn cost_hint(&self) -> CriterionCost {
+        CriterionCost::Cheap
+    }
 }
 
 /// I/O-dependent seal criteria.
@@ -177,9 +289,17 @@ impl IoSealCriteria for TimeoutSealer {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
     use zksync_utils::time::seconds_since_epoch;
 
-    use super::*;
+    use super::{
+        conditional_sealer::{AggregatedResolution, SealCriteriaBuilder},
+        *,
+    };
     use crate::state_keeper::tests::{
         create_execution_result, create_transaction, create_updates_manager,
     };
@@ -228,4 +348,373 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[derive(Debug)]
+    struct MockCriterion {
+        name: &'static str,
+        resolution: SealResolution,
+        cost: CriterionCost,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl SealCriterion for MockCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            _tx_count: usize,
+            _block_data: &SealData,
+            _tx_data: &SealData,
+            _protocol_version: ProtocolVersionId,
+        ) -> SealResolution {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.resolution.clone()
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            self.name
+        }
+
+        fn cost_hint(&self) -> CriterionCost {
+            self.cost
+        }
+    }
+
+    #[test]
+    fn expensive_criteria_are_skipped_after_exclusion() {
+        let calls = Arc::<AtomicUsize>::default();
+        let criterion = |resolution, cost| -> Box<dyn SealCriterion> {
+            Box::new(MockCriterion {
+                name: "mock",
+                resolution,
+                cost,
+                calls: calls.clone(),
+            })
+        };
+        let sealer = ConditionalSealer::with_sealers(
+            StateKeeperConfig::default(),
+            vec![
+                criterion(SealResolution::ExcludeAndSeal, CriterionCost::Cheap),
+                criterion(SealResolution::NoSeal, CriterionCost::Expensive),
+                criterion(SealResolution::Blocked, CriterionCost::Cheap),
+                criterion(SealResolution::NoSeal, CriterionCost::Cheap),
+                criterion(
+                    SealResolution::Unexecutable("test".into()),
+                    CriterionCost::Cheap,
+                ),
+                criterion(SealResolution::NoSeal, CriterionCost::Cheap),
+            ],
+        );
+
+        let resolution = sealer.should_seal_l1_batch(
+            1,
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::Unexecutable("test".into()));
+        // The expensive criterion is skipped. Blocking doesn't stop evaluation, but finding
+        // the transaction unexecutable does.
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn aggregated_resolution_lists_all_contributors() {
+        let criterion = |name, resolution| -> Box<dyn SealCriterion> {
+            Box::new(MockCriterion {
+                name,
+                resolution,
+                cost: CriterionCost::Cheap,
+                calls: Arc::default(),
+            })
+        };
+        let sealer = ConditionalSealer::with_sealers(
+            StateKeeperConfig::default(),
+            vec![
+                criterion("first", SealResolution::IncludeAndSeal),
+                criterion("second", SealResolution::NoSeal),
+                criterion("third", SealResolution::ExcludeAndSeal),
+                criterion("fourth", SealResolution::IncludeAndSeal),
+                criterion("fifth", SealResolution::ExcludeAndSeal),
+            ],
+        );
+
+        let aggregated = sealer.evaluate(
+            1,
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(
+            aggregated,
+            AggregatedResolution {
+                resolution: SealResolution::ExcludeAndSeal,
+                contributors: vec!["third", "fifth"],
+            }
+        );
+    }
+
+    #[test]
+    fn hard_gates_are_attributed_to_composite_children() {
+        let criterion = |name, resolution| -> Box<dyn SealCriterion> {
+            Box::new(MockCriterion {
+                name,
+                resolution,
+                cost: CriterionCost::Cheap,
+                calls: Arc::default(),
+            })
+        };
+        let composite = criteria::CompositeSealCriterion::new(
+            "all_of",
+            criteria::CombineMode::All,
+            vec![
+                criterion("first", SealResolution::IncludeAndSeal),
+                criterion("blocker", SealResolution::Blocked),
+            ],
+        );
+        let sealer = ConditionalSealer::with_sealers(
+            StateKeeperConfig::default(),
+            vec![Box::new(composite)],
+        );
+
+        let aggregated = sealer.evaluate(
+            1,
+            0,
+            1,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(aggregated.resolution, SealResolution::Blocked);
+        assert_eq!(aggregated.contributors, ["blocker"]);
+
+        let config = StateKeeperConfig {
+            blocked_tx_selectors: Some(vec!["0xa9059cbb".to_owned()]),
+            all_of_seal_criteria: Some(vec!["slots".to_owned(), "selector_blocklist".to_owned()]),
+            ..StateKeeperConfig::default()
+        };
+        let tx_data = SealData {
+            tx_selector: Some([0xa9, 0x05, 0x9c, 0xbb]),
+            ..SealData::default()
+        };
+        let sealer = ConditionalSealer::new(config).unwrap();
+        let reason = sealer.find_unexecutable_reason(&tx_data, ProtocolVersionId::latest());
+        assert_eq!(reason, Some("selector_blocklist"));
+    }
+
+    #[test]
+    fn sealer_creation_fails_on_invalid_selectors() {
+        let config = StateKeeperConfig {
+            revert_guard_selectors: Some(vec!["0x4e487b".to_owned()]),
+            ..StateKeeperConfig::default()
+        };
+        let err = ConditionalSealer::new(config).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("revert_guard_selectors"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn seal_criteria_builder_respects_disabled_criteria() {
+        let config = StateKeeperConfig {
+            fixed_cadence_txs_per_batch: Some(10),
+            disabled_seal_criteria: Some(vec!["tx_encoding_size".to_owned(), "mock".to_owned()]),
+            ..StateKeeperConfig::default()
+        };
+        let criteria = SealCriteriaBuilder::new(&config)
+            .unwrap()
+            .push(Box::new(MockCriterion {
+                name: "mock",
+                resolution: SealResolution::NoSeal,
+                cost: CriterionCost::Cheap,
+                calls: Arc::default(),
+            }))
+            .push(Box::new(MockCriterion {
+                name: "other_mock",
+                resolution: SealResolution::NoSeal,
+                cost: CriterionCost::Cheap,
+                calls: Arc::default(),
+            }))
+            .build();
+        let names: Vec<_> = criteria
+            .iter()
+            .map(|criterion| criterion.prom_criterion_name())
+            .collect();
+
+        assert!(names.contains(&"slots"), "{names:?}");
+        assert!(names.contains(&"fixed_cadence"), "{names:?}");
+        assert!(names.contains(&"other_mock"), "{names:?}");
+        assert!(!names.contains(&"tx_encoding_size"), "{names:?}");
+        assert!(!names.contains(&"mock"), "{names:?}");
+    }
+
+    #[test]
+    fn timeout_seal_criterion_is_opt_in() {
+        let has_timeout_criterion = |config: &StateKeeperConfig| {
+            SealCriteriaBuilder::new(config)
+                .unwrap()
+                .build()
+                .iter()
+                .any(|criterion| criterion.prom_criterion_name() == "timeout")
+        };
+
+        let mut config = StateKeeperConfig::default();
+        assert!(!has_timeout_criterion(&config));
+        config.use_timeout_seal_criterion = true;
+        assert!(has_timeout_criterion(&config));
+    }
+
+    #[test]
+    fn seal_criteria_builder_combines_all_of_criteria() {
+        let config = StateKeeperConfig {
+            transaction_slots: 1,
+            fixed_cadence_txs_per_batch: Some(2),
+            disabled_seal_criteria: Some(vec!["tx_encoding_size".to_owned()]),
+            all_of_seal_criteria: Some(vec![
+                "slots".to_owned(),
+                "fixed_cadence".to_owned(),
+                "tx_encoding_size".to_owned(),
+            ]),
+            ..StateKeeperConfig::default()
+        };
+        let criteria = SealCriteriaBuilder::new(&config).unwrap().build();
+        let names: Vec<_> = criteria
+            .iter()
+            .map(|criterion| criterion.prom_criterion_name())
+            .collect();
+        assert!(names.contains(&"all_of"), "{names:?}");
+        assert!(!names.contains(&"slots"), "{names:?}");
+        assert!(!names.contains(&"fixed_cadence"), "{names:?}");
+        assert!(!names.contains(&"tx_encoding_size"), "{names:?}");
+
+        let sealer = ConditionalSealer::with_sealers(config, criteria);
+        let block_data = SealData::default();
+        // Only `slots` wants to seal the batch.
+        let resolution = sealer.evaluate(
+            1,
+            0,
+            1,
+            &block_data,
+            &block_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution.resolution, SealResolution::NoSeal);
+        // Both combined criteria want to seal the batch.
+        let resolution = sealer.evaluate(
+            1,
+            0,
+            2,
+            &block_data,
+            &block_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution.resolution, SealResolution::IncludeAndSeal);
+        assert_eq!(resolution.contributors, ["slots"]);
+    }
+
+    #[test]
+    fn resolutions_from_conditions() {
+        assert_eq!(
+            SealResolution::include_if(true, "test"),
+            SealResolution::IncludeAndSeal
+        );
+        assert_eq!(
+            SealResolution::include_if(false, "test"),
+            SealResolution::NoSeal
+        );
+        assert_eq!(
+            SealResolution::exclude_if(true, "test"),
+            SealResolution::ExcludeAndSeal
+        );
+        assert_eq!(
+            SealResolution::exclude_if(false, "test"),
+            SealResolution::NoSeal
+        );
+    }
+
+    #[test]
+    fn merging_resolutions() {
+        use SealResolution::{Blocked, ExcludeAndSeal, IncludeAndSeal, NoSeal, Unexecutable};
+
+        let unexecutable = || Unexecutable("test".to_owned());
+        // `(lhs, rhs, merged)` for all pairs of resolutions.
+        let truth_table = [
+            (NoSeal, NoSeal, NoSeal),
+            (NoSeal, IncludeAndSeal, IncludeAndSeal),
+            (NoSeal, ExcludeAndSeal, ExcludeAndSeal),
+            (NoSeal, Blocked, Blocked),
+            (NoSeal, unexecutable(), unexecutable()),
+            (IncludeAndSeal, NoSeal, IncludeAndSeal),
+            (IncludeAndSeal, IncludeAndSeal, IncludeAndSeal),
+            (IncludeAndSeal, ExcludeAndSeal, ExcludeAndSeal),
+            (IncludeAndSeal, Blocked, Blocked),
+            (IncludeAndSeal, unexecutable(), unexecutable()),
+            (ExcludeAndSeal, NoSeal, ExcludeAndSeal),
+            (ExcludeAndSeal, IncludeAndSeal, ExcludeAndSeal),
+            (ExcludeAndSeal, ExcludeAndSeal, ExcludeAndSeal),
+            (ExcludeAndSeal, Blocked, Blocked),
+            (ExcludeAndSeal, unexecutable(), unexecutable()),
+            (Blocked, NoSeal, Blocked),
+            (Blocked, IncludeAndSeal, Blocked),
+            (Blocked, ExcludeAndSeal, Blocked),
+            (Blocked, Blocked, Blocked),
+            (Blocked, unexecutable(), unexecutable()),
+            (unexecutable(), NoSeal, unexecutable()),
+            (unexecutable(), IncludeAndSeal, unexecutable()),
+            (unexecutable(), ExcludeAndSeal, unexecutable()),
+            (unexecutable(), Blocked, unexecutable()),
+            (unexecutable(), unexecutable(), unexecutable()),
+        ];
+        for (lhs, rhs, expected) in truth_table {
+            let merged = lhs.clone().merge(rhs.clone());
+            assert_eq!(merged, expected, "{lhs:?}.merge({rhs:?})");
+        }
+
+        let merged = Unexecutable("first".to_owned()).merge(Unexecutable("second".to_owned()));
+        assert_eq!(merged, Unexecutable("first".to_owned()));
+    }
+
+    #[test]
+    fn severity_is_consistent_with_merging() {
+        use SealResolution::{Blocked, ExcludeAndSeal, IncludeAndSeal, NoSeal, Unexecutable};
+
+        let resolutions = [
+            NoSeal,
+            IncludeAndSeal,
+            ExcludeAndSeal,
+            Blocked,
+            Unexecutable("test".to_owned()),
+        ];
+        for lhs in &resolutions {
+            for rhs in &resolutions {
+                let merged = lhs.clone().merge(rhs.clone());
+                let expected_severity = lhs.severity().max(rhs.severity());
+                assert_eq!(merged.severity(), expected_severity, "{lhs:?}, {rhs:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn blocked_resolution_precedence() {
+        for resolution in [
+            SealResolution::NoSeal,
+            SealResolution::IncludeAndSeal,
+            SealResolution::ExcludeAndSeal,
+        ] {
+            let merged = resolution.clone().merge(SealResolution::Blocked);
+            assert_eq!(merged, SealResolution::Blocked);
+            let merged = SealResolution::Blocked.merge(resolution);
+            assert_eq!(merged, SealResolution::Blocked);
+        }
+        assert!(!SealResolution::Blocked.should_seal());
+
+        let unexecutable = SealResolution::Unexecutable("test".to_owned());
+        let merged = SealResolution::Blocked.merge(unexecutable.clone());
+        assert_eq!(merged, unexecutable);
+    }
 }
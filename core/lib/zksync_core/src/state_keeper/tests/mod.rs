@@ -1,7 +1,7 @@
 use std::{
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     time::Instant,
 };
@@ -42,7 +42,7 @@ use crate::{
         keeper::POLL_WAIT_DURATION,
         seal_criteria::{
             criteria::{GasCriterion, SlotsCriterion},
-            ConditionalSealer,
+            ConditionalSealer, SealCriterion, SealData, SealResolution,
         },
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
@@ -398,6 +398,77 @@ async fn rejected_tx() {
         .await;
 }
 
+/// Seal criterion blocking the L1 batch on a single call, and recording times of all calls.
+#[derive(Debug)]
+struct BlockOnceCriterion {
+    blocked_call: usize,
+    call_times: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl SealCriterion for BlockOnceCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let mut call_times = self.call_times.lock().unwrap();
+        call_times.push(Instant::now());
+        if call_times.len() == self.blocked_call + 1 {
+            SealResolution::Blocked
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "block_once"
+    }
+}
+
+#[tokio::test]
+async fn blocked_batch_is_retried_after_backoff() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let call_times = Arc::<Mutex<Vec<Instant>>>::default();
+    let blocking_criterion = BlockOnceCriterion {
+        blocked_call: 1,
+        call_times: call_times.clone(),
+    };
+    let sealer = ConditionalSealer::with_sealers(
+        config,
+        vec![Box::new(SlotsCriterion), Box::new(blocking_criterion)],
+    );
+
+    let blocked_tx = random_tx(2);
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock with 1st tx")
+        .next_tx("Tx -> Batch blocked", blocked_tx.clone(), successful_exec())
+        .tx_rollback("Tx rolled back while batch is blocked", blocked_tx.clone())
+        .next_tx(
+            "Same tx after batch is unblocked",
+            blocked_tx,
+            successful_exec(),
+        )
+        .miniblock_sealed("Miniblock with 2nd tx")
+        .batch_sealed_with("Batch sealed with 2 txs", move |_, updates, _| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 2);
+            // The state keeper must back off after the batch is blocked rather than immediately retrying.
+            let call_times = call_times.lock().unwrap();
+            let backoff = call_times[2].duration_since(call_times[1]);
+            assert!(backoff >= POLL_WAIT_DURATION, "{backoff:?}");
+        })
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn bootloader_tip_out_of_gas_flow() {
     let config = StateKeeperConfig {
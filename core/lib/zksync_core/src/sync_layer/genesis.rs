@@ -1,8 +1,10 @@
+use std::sync::Arc;
+
 use anyhow::Context as _;
 use zksync_dal::StorageProcessor;
 use zksync_types::{
     block::DeployedContract, protocol_version::L1VerifierConfig,
-    system_contracts::get_system_smart_contracts, AccountTreeId, Address, L1BatchNumber, L2ChainId,
+    system_contracts::system_contract_registry, AccountTreeId, Address, L1BatchNumber, L2ChainId,
     H256,
 };
 
@@ -47,9 +49,9 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
     // Load the list of addresses that are known to contain system contracts at any point in time.
     // Not every of these addresses is guaranteed to be present in the genesis state, but we'll iterate through
     // them and try to fetch the contract bytecode for each of them.
-    let system_contract_addresses: Vec<_> = get_system_smart_contracts()
-        .into_iter()
-        .map(|contract| *contract.account_id.address())
+    let system_contract_addresses: Vec<_> = system_contract_registry()
+        .iter()
+        .map(|&(_, address)| address)
         .collect();
 
     // These have to be *initial* base contract hashes of main node
@@ -64,7 +66,7 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
     // So, to collect the list of system contracts, we compute the corresponding storage slots and request
     // the state at genesis block to fetch the hash of the corresponding contract.
     // Then, we can fetch the factory dependency bytecode to fully recover the contract.
-    let mut system_contracts: Vec<DeployedContract> =
+    let mut system_contracts: Vec<Arc<DeployedContract>> =
         Vec::with_capacity(system_contract_addresses.len());
 
     for system_contract_address in system_contract_addresses {
@@ -78,7 +80,7 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
             continue;
         };
         let contract = DeployedContract::new(AccountTreeId::new(system_contract_address), bytecode);
-        system_contracts.push(contract);
+        system_contracts.push(Arc::new(contract));
     }
     assert!(
         !system_contracts.is_empty(),
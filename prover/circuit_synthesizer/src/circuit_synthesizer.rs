@@ -142,7 +142,10 @@ impl JobProcessor for CircuitSynthesizer {
             "Attempting to fetch job types: {:?}",
             self.allowed_circuit_types
         );
-        let mut storage = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut storage = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let protocol_versions = storage
             .protocol_versions_dal()
             .protocol_version_for(&self.vk_commitments)
@@ -183,8 +186,7 @@ impl JobProcessor for CircuitSynthesizer {
 
     async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
         let res = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .prover_dal()
@@ -228,8 +230,7 @@ impl JobProcessor for CircuitSynthesizer {
 
         while now.elapsed() < self.config.prover_instance_wait_timeout() {
             let prover = self
-                .prover_connection_pool
-                .access_storage()
+                .acquire_connection(self.prover_connection_pool.access_storage())
                 .await
                 .unwrap()
                 .gpu_prover_queue_dal()
@@ -112,7 +112,10 @@ impl JobProcessor for ProofCompressor {
     const SERVICE_NAME: &'static str = "ProofCompressor";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut conn = self.pool.access_storage().await.unwrap();
+        let mut conn = self
+            .acquire_connection(self.pool.access_storage())
+            .await
+            .unwrap();
         let pod_name = get_current_pod_name();
         let Some(l1_batch_number) = conn
             .fri_proof_compressor_dal()
@@ -147,8 +150,7 @@ impl JobProcessor for ProofCompressor {
     }
 
     async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.pool
-            .access_storage()
+        self.acquire_connection(self.pool.access_storage())
             .await
             .unwrap()
             .fri_proof_compressor_dal()
@@ -201,8 +203,7 @@ impl JobProcessor for ProofCompressor {
             .blob_save_time
             .observe(blob_save_started_at.elapsed());
 
-        self.pool
-            .access_storage()
+        self.acquire_connection(self.pool.access_storage())
             .await
             .unwrap()
             .fri_proof_compressor_dal()
@@ -217,8 +218,7 @@ impl JobProcessor for ProofCompressor {
 
     async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .pool
-            .access_storage()
+            .acquire_connection(self.pool.access_storage())
             .await
             .context("failed to acquire DB connection for ProofCompressor")?;
         prover_storage
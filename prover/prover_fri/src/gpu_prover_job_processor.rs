@@ -215,8 +215,7 @@ pub mod gpu_prover {
                 }
                 Ok(item) => {
                     if is_full {
-                        self.prover_connection_pool
-                            .access_storage()
+                        self.acquire_connection(self.prover_connection_pool.access_storage())
                             .await
                             .unwrap()
                             .fri_gpu_prover_queue_dal()
@@ -240,8 +239,7 @@ pub mod gpu_prover {
         }
 
         async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-            self.prover_connection_pool
-                .access_storage()
+            self.acquire_connection(self.prover_connection_pool.access_storage())
                 .await
                 .unwrap()
                 .fri_prover_jobs_dal()
@@ -273,7 +271,10 @@ pub mod gpu_prover {
         ) -> anyhow::Result<()> {
             METRICS.gpu_total_proving_time.observe(started_at.elapsed());
 
-            let mut storage_processor = self.prover_connection_pool.access_storage().await.unwrap();
+            let mut storage_processor = self
+                .acquire_connection(self.prover_connection_pool.access_storage())
+                .await
+                .unwrap();
             save_proof(
                 job_id,
                 started_at,
@@ -293,8 +294,7 @@ pub mod gpu_prover {
 
         async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
             let mut prover_storage = self
-                .prover_connection_pool
-                .access_storage()
+                .acquire_connection(self.prover_connection_pool.access_storage())
                 .await
                 .context("failed to acquire DB connection for Prover")?;
             prover_storage
@@ -197,7 +197,10 @@ impl JobProcessor for Prover {
     const SERVICE_NAME: &'static str = "FriCpuProver";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut storage = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut storage = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let Some(prover_job) = fetch_next_circuit(
             &mut storage,
             &*self.blob_store,
@@ -212,8 +215,7 @@ impl JobProcessor for Prover {
     }
 
     async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.prover_connection_pool
-            .access_storage()
+        self.acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .fri_prover_jobs_dal()
@@ -245,7 +247,10 @@ impl JobProcessor for Prover {
     ) -> anyhow::Result<()> {
         METRICS.cpu_total_proving_time.observe(started_at.elapsed());
 
-        let mut storage_processor = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut storage_processor = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         save_proof(
             job_id,
             started_at,
@@ -265,8 +270,7 @@ impl JobProcessor for Prover {
 
     async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for Prover")?;
         prover_storage
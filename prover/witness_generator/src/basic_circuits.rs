@@ -185,7 +185,10 @@ impl JobProcessor for BasicWitnessGenerator {
     const SERVICE_NAME: &'static str = "fri_basic_circuit_witness_generator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut prover_connection = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut prover_connection = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let last_l1_batch_to_process = self.config.last_l1_batch_to_process();
         let pod_name = get_current_pod_name();
         match prover_connection
@@ -215,8 +218,7 @@ impl JobProcessor for BasicWitnessGenerator {
     }
 
     async fn save_failure(&self, job_id: L1BatchNumber, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .access_storage()
+        self.acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .fri_witness_generator_dal()
@@ -281,8 +283,7 @@ impl JobProcessor for BasicWitnessGenerator {
 
     async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for BasicWitnessGenerator")?;
         prover_storage
@@ -115,7 +115,10 @@ impl JobProcessor for LeafAggregationWitnessGenerator {
     const SERVICE_NAME: &'static str = "fri_leaf_aggregation_witness_generator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut prover_connection = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut prover_connection = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let pod_name = get_current_pod_name();
         let Some(metadata) = prover_connection
             .fri_witness_generator_dal()
@@ -134,8 +137,7 @@ impl JobProcessor for LeafAggregationWitnessGenerator {
     }
 
     async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .access_storage()
+        self.acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .fri_witness_generator_dal()
@@ -190,8 +192,7 @@ impl JobProcessor for LeafAggregationWitnessGenerator {
 
     async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for LeafAggregationWitnessGenerator")?;
         prover_storage
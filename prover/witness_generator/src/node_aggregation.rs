@@ -148,7 +148,10 @@ impl JobProcessor for NodeAggregationWitnessGenerator {
     const SERVICE_NAME: &'static str = "fri_node_aggregation_witness_generator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut prover_connection = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut prover_connection = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let pod_name = get_current_pod_name();
         let Some(metadata) = prover_connection
             .fri_witness_generator_dal()
@@ -167,8 +170,7 @@ impl JobProcessor for NodeAggregationWitnessGenerator {
     }
 
     async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .access_storage()
+        self.acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .fri_witness_generator_dal()
@@ -216,8 +218,7 @@ impl JobProcessor for NodeAggregationWitnessGenerator {
 
     async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for NodeAggregationWitnessGenerator")?;
         prover_storage
@@ -117,7 +117,10 @@ impl JobProcessor for SchedulerWitnessGenerator {
     const SERVICE_NAME: &'static str = "fri_scheduler_witness_generator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut prover_connection = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut prover_connection = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let pod_name = get_current_pod_name();
         let Some(l1_batch_number) = prover_connection
             .fri_witness_generator_dal()
@@ -140,8 +143,7 @@ impl JobProcessor for SchedulerWitnessGenerator {
     }
 
     async fn save_failure(&self, job_id: L1BatchNumber, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .access_storage()
+        self.acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .unwrap()
             .fri_witness_generator_dal()
@@ -180,7 +182,10 @@ impl JobProcessor for SchedulerWitnessGenerator {
         WITNESS_GENERATOR_METRICS.blob_save_time[&AggregationRound::Scheduler.into()]
             .observe(blob_save_started_at.elapsed());
 
-        let mut prover_connection = self.prover_connection_pool.access_storage().await.unwrap();
+        let mut prover_connection = self
+            .acquire_connection(self.prover_connection_pool.access_storage())
+            .await
+            .unwrap();
         let mut transaction = prover_connection.start_transaction().await.unwrap();
         let protocol_version_id = transaction
             .fri_witness_generator_dal()
@@ -215,8 +220,7 @@ impl JobProcessor for SchedulerWitnessGenerator {
 
     async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .prover_connection_pool
-            .access_storage()
+            .acquire_connection(self.prover_connection_pool.access_storage())
             .await
             .context("failed to acquire DB connection for SchedulerWitnessGenerator")?;
         prover_storage
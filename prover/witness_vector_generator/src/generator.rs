@@ -82,7 +82,10 @@ impl JobProcessor for WitnessVectorGenerator {
     const SERVICE_NAME: &'static str = "WitnessVectorGenerator";
 
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
-        let mut storage = self.pool.access_storage().await.unwrap();
+        let mut storage = self
+            .acquire_connection(self.pool.access_storage())
+            .await
+            .unwrap();
         let Some(job) = fetch_next_circuit(
             &mut storage,
             &*self.blob_store,
@@ -97,8 +100,7 @@ impl JobProcessor for WitnessVectorGenerator {
     }
 
     async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.pool
-            .access_storage()
+        self.acquire_connection(self.pool.access_storage())
             .await
             .unwrap()
             .fri_prover_jobs_dal()
@@ -139,8 +141,7 @@ impl JobProcessor for WitnessVectorGenerator {
 
         while now.elapsed() < self.config.prover_instance_wait_timeout() {
             let prover = self
-                .pool
-                .access_storage()
+                .acquire_connection(self.pool.access_storage())
                 .await
                 .unwrap()
                 .fri_gpu_prover_queue_dal()
@@ -197,8 +198,7 @@ impl JobProcessor for WitnessVectorGenerator {
 
     async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
         let mut prover_storage = self
-            .pool
-            .access_storage()
+            .acquire_connection(self.pool.access_storage())
             .await
             .context("failed to acquire DB connection for WitnessVectorGenerator")?;
         prover_storage